@@ -1,7 +1,14 @@
-use crate::utils::trie::Trie;
+use crate::utils::{lines::sum_lines, trie::Trie};
+use crate::utils::input::{check_input, InputSpec};
 
 // https://adventofcode.com/2023/day/1
 
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_alphanumeric(),
+};
+
 fn solve_part_1(input: &str) -> u64 {
 
     fn calculate_calibration_value(line: &str) -> u64 {
@@ -11,7 +18,7 @@ fn solve_part_1(input: &str) -> u64 {
         ((first_num * 10) + last_num) as u64
     }
 
-    input.lines().map(calculate_calibration_value).sum()
+    sum_lines(input, calculate_calibration_value)
 }
 
 fn solve_part_2(input: &str) -> u64 {
@@ -111,11 +118,13 @@ fn solve_part_2(input: &str) -> u64 {
         ((first_num * 10) + last_num) as u64
     }
 
-    input.lines().map(calculate_calibration_value).sum()
+    sum_lines(input, calculate_calibration_value)
 }
 
 pub fn answers() {
-    let input = include_str!("input.txt").trim();
+    let input = include_str!("input.txt");
+    check_input(input, None, &INPUT_SPEC);
+    let input = input.trim();
 
     println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
     println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
@@ -1,17 +1,175 @@
-// https://adventofcode.com/2023/day/CHANGEME
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
 
-fn solve_part_1(input: &str) -> u64 {
-    todo!();
+// https://adventofcode.com/2023/day/10
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| "|-LJ7F.S".contains(c),
+};
+
+type Position = (usize, usize);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [Direction::North, Direction::South, Direction::West, Direction::East];
+
+    fn opposite(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+
+    fn step(&self, (row, col): Position, rows: usize, cols: usize) -> Option<Position> {
+        match self {
+            Self::North if row > 0 => Some((row - 1, col)),
+            Self::South if row + 1 < rows => Some((row + 1, col)),
+            Self::West if col > 0 => Some((row, col - 1)),
+            Self::East if col + 1 < cols => Some((row, col + 1)),
+            _ => None,
+        }
+    }
+}
+
+// The two directions a pipe connects, or None for ground and the start tile
+fn connections(tile: u8) -> Option<[Direction; 2]> {
+    match tile {
+        b'|' => Some([Direction::North, Direction::South]),
+        b'-' => Some([Direction::West, Direction::East]),
+        b'L' => Some([Direction::North, Direction::East]),
+        b'J' => Some([Direction::North, Direction::West]),
+        b'7' => Some([Direction::South, Direction::West]),
+        b'F' => Some([Direction::South, Direction::East]),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+struct Sketch<'a> {
+    tiles: Vec<&'a [u8]>,
+    start: Position,
+}
+
+impl<'a> Sketch<'a> {
+    fn parse(input: &'a str) -> Option<Self> {
+        let tiles: Vec<&[u8]> = input.lines().map(|line| line.as_bytes()).collect();
+
+        let start = tiles.iter().enumerate().find_map(|(row, line)| {
+            line.iter().position(|&tile| tile == b'S').map(|col| (row, col))
+        })?;
+
+        Some(Self {
+            tiles,
+            start,
+        })
+    }
+
+    fn rows(&self) -> usize {
+        self.tiles.len()
+    }
+
+    fn cols(&self) -> usize {
+        self.tiles[0].len()
+    }
+
+    fn connects(&self, position: Position, direction: Direction) -> bool {
+        connections(self.tiles[position.0][position.1]).is_some_and(|pipe| pipe.contains(&direction))
+    }
+
+    // Tiles of the loop through the start, in the order they are walked
+    fn find_loop(&self) -> Option<Vec<Position>> {
+        let (rows, cols) = (self.rows(), self.cols());
+
+        // The start connects to every neighbour with a pipe pointing back at it
+        let mut direction = Direction::ALL.into_iter().find(|direction| {
+            direction.step(self.start, rows, cols).is_some_and(|next| self.connects(next, direction.opposite()))
+        })?;
+
+        let mut path = vec![self.start];
+        let mut position = self.start;
+
+        loop {
+            position = direction.step(position, rows, cols)?;
+            if position == self.start {
+                return Some(path);
+            }
+
+            // A pipe that doesn't lead back the way we came breaks the loop
+            if !self.connects(position, direction.opposite()) {
+                return None;
+            }
+
+            path.push(position);
+
+            let came_from = direction.opposite();
+            direction = connections(self.tiles[position.0][position.1])?.into_iter().find(|&d| d != came_from)?;
+        }
+    }
 }
 
-fn solve_part_2(input: &str) -> u64 {
-    todo!();
+// Tiles strictly inside a loop, from its area (shoelace formula) and Pick's theorem:
+// area = interior + boundary / 2 - 1
+fn enclosed_tiles(path: &[Position]) -> u64 {
+    let twice_area: i64 = path.iter().zip(path.iter().cycle().skip(1)).map(|(&(r1, c1), &(r2, c2))| {
+        (c1 as i64 * r2 as i64) - (c2 as i64 * r1 as i64)
+    }).sum();
+
+    (twice_area.unsigned_abs() - path.len() as u64) / 2 + 1
+}
+
+const NO_LOOP: &str = "Expected a closed loop of pipes through S";
+
+fn solve_part_1(input: &str) -> Result<u64, &'static str> {
+    let path = Sketch::parse(input).and_then(|sketch| sketch.find_loop()).ok_or(NO_LOOP)?;
+
+    Ok(path.len() as u64 / 2)
+}
+
+fn solve_part_2(input: &str) -> Result<u64, &'static str> {
+    let path = Sketch::parse(input).and_then(|sketch| sketch.find_loop()).ok_or(NO_LOOP)?;
+
+    Ok(enclosed_tiles(&path))
 }
 
 pub fn answers() {
-    // let input = include_str!("input.txt").trim();
-    let input = include_str!("test.txt").trim();
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1("..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ..."), Ok(8));
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(80));
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), Ok(10));
+    }
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    #[test]
+    fn broken_loop() {
+        // The - leads east into the F of a side loop, which has no pipe back west to it
+        assert_eq!(solve_part_1(".....\nS-F7.\n..LJ.\n....."), Err(NO_LOOP));
+    }
 }
@@ -1,7 +1,15 @@
 use std::collections::HashSet;
 
+use crate::utils::input::{check_input, InputSpec};
+
 // https://adventofcode.com/2023/day/11
 
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| ".#".contains(c),
+};
+
 fn parse_image(image: &str, galaxies: &mut Vec<(usize, usize)>, occupied_rows: &mut HashSet<usize>, occupied_cols: &mut HashSet<usize>) {
     for (row, line) in image.lines().enumerate() {
         for (col, c) in line.chars().enumerate() {
@@ -55,8 +63,10 @@ fn solve_part_2(input: &str) -> usize {
 }
 
 pub fn answers() {
-    let input = include_str!("input.txt").trim();
-    // let input = include_str!("test.txt").trim();
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
 
     // println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
     println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
@@ -1,8 +1,17 @@
 use regex::Regex;
 use once_cell::sync::Lazy;
 
+use crate::utils::lines::sum_lines;
+use crate::utils::input::{check_input, InputSpec};
+
 // https://adventofcode.com/2023/day/12
 
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_digit() || "?.#, ".contains(c),
+};
+
 static SPRING_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?<springs>(\?|\#|\.)+)\s(?<groups>[0-9,]+)$").unwrap());
 
 fn parse_line(line: &str, fold_factor: usize) -> (String, Vec<usize>) {
@@ -83,16 +92,18 @@ fn count_arrangements(line: &str, fold_factor: usize) -> u64 {
 }
 
 fn solve_part_1(input: &str) -> u64 {
-    input.lines().map(|line| count_arrangements(line, 1)).sum()
+    sum_lines(input, |line| count_arrangements(line, 1))
 }
 
 fn solve_part_2(input: &str) -> u64 {
-    input.lines().map(|line| count_arrangements(line, 5)).sum()
+    sum_lines(input, |line| count_arrangements(line, 5))
 }
 
 pub fn answers() {
-    let input = include_str!("input.txt").trim();
-    // let input = include_str!("test.txt").trim();
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
 
     println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
     println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
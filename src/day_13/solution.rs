@@ -1,8 +1,16 @@
 use regex::Regex;
 use once_cell::sync::Lazy;
 
+use crate::utils::input::{check_input, InputSpec};
+
 // https://adventofcode.com/2023/day/13
 
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: true,
+    allowed: |c| ".#".contains(c),
+};
+
 static PATTERN_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?<pattern>(?:[\.|\#]+\n)+)\n").unwrap());
 
 type DataLine = Vec<char>;
@@ -160,8 +168,10 @@ fn solve_part_2(input: &str) -> u64 {
 }
 
 pub fn answers() {
-    let mut input = String::from(include_str!("input.txt").trim());
-    // let mut input = String::from(include_str!("test.txt").trim());
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let mut input = String::from(input.trim());
     input.push_str("\n\n");
 
     // println!("[P1 :: INFO] Answer: {}", solve_part_1(&input));
@@ -0,0 +1 @@
+pub mod solution;
\ No newline at end of file
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::utils::input::{check_input, InputSpec};
+
+// https://adventofcode.com/2023/day/14
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| "O#.".contains(c),
+};
+
+const SPIN_CYCLES: usize = 1_000_000_000;
+
+type Platform = Vec<Vec<u8>>;
+
+fn parse_platform(input: &str) -> Platform {
+    input.lines().map(|line| line.as_bytes().to_vec()).collect()
+}
+
+// Rolls every round rock as far north as it goes
+fn tilt_north(platform: &mut Platform) {
+    for col in 0..platform[0].len() {
+        let mut free_row = 0;
+
+        for row in 0..platform.len() {
+            match platform[row][col] {
+                b'#' => free_row = row + 1,
+                b'O' => {
+                    platform[row][col] = b'.';
+                    platform[free_row][col] = b'O';
+                    free_row += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn rotate_clockwise(platform: &Platform) -> Platform {
+    let rows = platform.len();
+
+    (0..platform[0].len()).map(|col| (0..rows).map(|row| platform[rows - 1 - row][col]).collect()).collect()
+}
+
+// Tilts north, west, south and then east. Rotating clockwise after each tilt turns the next
+// direction into north, and four rotations leave the platform facing the way it started.
+fn spin_cycle(platform: Platform) -> Platform {
+    (0..4).fold(platform, |mut platform, _| {
+        tilt_north(&mut platform);
+        rotate_clockwise(&platform)
+    })
+}
+
+// Each round rock adds the number of rows from it to the south edge, inclusive
+fn north_load(platform: &Platform) -> u64 {
+    platform.iter().enumerate().map(|(row, line)| {
+        (platform.len() - row) as u64 * line.iter().filter(|&&tile| tile == b'O').count() as u64
+    }).sum()
+}
+
+fn solve_part_1(input: &str) -> u64 {
+    let mut platform = parse_platform(input);
+    tilt_north(&mut platform);
+
+    north_load(&platform)
+}
+
+fn solve_part_2(input: &str) -> u64 {
+    let mut platform = parse_platform(input);
+    let mut seen = HashMap::<Platform, usize>::new();
+
+    // The rocks settle into a repeating pattern, so skip ahead by whole periods once one shows up
+    let mut cycle = 0;
+    while cycle < SPIN_CYCLES {
+        if let Some(previous) = seen.insert(platform.clone(), cycle) {
+            let period = cycle - previous;
+            let remaining = (SPIN_CYCLES - cycle) % period;
+
+            for _ in 0..remaining {
+                platform = spin_cycle(platform);
+            }
+
+            break;
+        }
+
+        platform = spin_cycle(platform);
+        cycle += 1;
+    }
+
+    north_load(&platform)
+}
+
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
+    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), 136);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), 64);
+    }
+}
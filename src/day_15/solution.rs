@@ -1,7 +1,19 @@
-use std::collections::HashMap;
+use std::fmt;
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
 
 // https://adventofcode.com/2023/day/15
 
+// The initialization sequence is a single long line
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 1,
+    grid: false,
+    allowed: |c| c.is_ascii_lowercase() || c.is_ascii_digit() || "=-,".contains(c),
+};
+
+const NUM_BOXES: usize = 256;
+
 fn hash(s: &str) -> u64 {
     let mut hash: u64 = 0;
 
@@ -14,55 +26,183 @@ fn hash(s: &str) -> u64 {
     hash
 }
 
-fn remove_lens(lens: &str, hashmap: &mut HashMap<u64, Vec<(String, u64)>>) {
-    let key = hash(lens);
-    hashmap.entry(key).and_modify(|v| { v.retain(|l| l.0 != lens) });
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Operation {
+    Remove,
+    Insert(u8),
 }
 
-fn add_lens(lens: &str, focal_length: u64, hashmap: &mut HashMap<u64, Vec<(String, u64)>>) {
-    let key = hash(lens);
-    let mut lenses = hashmap.entry(key).or_insert(Vec::new());
+#[derive(Debug)]
+struct Step<'a> {
+    label: &'a str,
+    op: Operation,
+}
 
-    for l in lenses.iter_mut() {
-        if l.0 == lens {
-            l.1 = focal_length;
-            return;
-        } 
+#[derive(Debug)]
+enum StepErrorKind {
+    EmptyLabel,
+    InvalidLabel(char),
+    MissingOperation,
+    InvalidFocalLength(String),
+}
+
+#[derive(Debug)]
+struct StepError {
+    // Position of the step in the comma-separated sequence
+    index: usize,
+    step: String,
+    kind: StepErrorKind,
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Step {} ({:?}) ", self.index, self.step)?;
+
+        match &self.kind {
+            StepErrorKind::EmptyLabel => write!(f, "has no label"),
+            StepErrorKind::InvalidLabel(c) => write!(f, "has {:?} in its label, expected lowercase letters", c),
+            StepErrorKind::MissingOperation => write!(f, "ends without '-' or '='"),
+            StepErrorKind::InvalidFocalLength(length) => write!(f, "has focal length {:?}, expected 1-9", length),
+        }
     }
+}
 
-    lenses.push((lens.to_owned(), focal_length));
+impl<'a> Step<'a> {
+    fn parse(step: &'a str, index: usize) -> Result<Self, StepError> {
+        let error = |kind| StepError { index, step: step.to_owned(), kind };
+
+        let label_end = step.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(step.len());
+        let (label, rest) = step.split_at(label_end);
+
+        let op = match rest.chars().next() {
+            None => return Err(error(StepErrorKind::MissingOperation)),
+            Some('-') if rest.len() > 1 => return Err(error(StepErrorKind::InvalidLabel('-'))),
+            Some('-') => Operation::Remove,
+            Some('=') => match rest[1..].parse::<u8>() {
+                Ok(focal_length @ 1..=9) if rest.len() == 2 => Operation::Insert(focal_length),
+                _ => return Err(error(StepErrorKind::InvalidFocalLength(rest[1..].to_owned()))),
+            },
+            Some(c) => return Err(error(StepErrorKind::InvalidLabel(c))),
+        };
+
+        if label.is_empty() {
+            return Err(error(StepErrorKind::EmptyLabel));
+        }
+
+        Ok(Self {
+            label,
+            op,
+        })
+    }
+}
+
+fn parse_sequence(sequence: &str) -> Result<Vec<Step<'_>>, StepError> {
+    sequence.split(',').enumerate().filter(|&(_, s)| !s.is_empty()).map(|(i, s)| Step::parse(s, i)).collect()
+}
+
+#[derive(Debug, Clone)]
+struct Lens {
+    label: String,
+    focal_length: u8,
 }
 
-fn configure_lens(step: &str, hashmap: &mut HashMap<u64, Vec<(String, u64)>>) {
-    if step.contains("-") {
-        remove_lens(&step[..step.len() - 1], hashmap)
-    } else if step.contains("=") {
-        let mut _step = step.split('=');
-        let lens = _step.next().unwrap();
-        let focal_length = _step.next().unwrap().parse::<u64>().unwrap();
+#[derive(Debug, Clone)]
+struct LensLibrary {
+    boxes: [Vec<Lens>; NUM_BOXES],
+}
+
+impl LensLibrary {
+    fn new() -> Self {
+        Self {
+            boxes: std::array::from_fn(|_| Vec::new()),
+        }
+    }
 
-        add_lens(lens, focal_length, hashmap);
-    } else {
-        panic!("Unrecognized step pattern: {}", step);
+    fn remove_lens(&mut self, label: &str) {
+        self.boxes[hash(label) as usize].retain(|lens| lens.label != label);
     }
+
+    fn add_lens(&mut self, label: &str, focal_length: u8) {
+        let lenses = &mut self.boxes[hash(label) as usize];
+
+        match lenses.iter_mut().find(|lens| lens.label == label) {
+            Some(lens) => lens.focal_length = focal_length,
+            None => lenses.push(Lens { label: label.to_owned(), focal_length }),
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        match step.op {
+            Operation::Remove => self.remove_lens(step.label),
+            Operation::Insert(focal_length) => self.add_lens(step.label, focal_length),
+        }
+    }
+
+    fn focusing_power(&self) -> u64 {
+        self.boxes.iter().enumerate().map(|(i, lenses)| {
+            lenses.iter().enumerate().fold(0, |acc, (slot, lens)| acc + ((i + 1) * (slot + 1)) as u64 * lens.focal_length as u64)
+        }).sum()
+    }
+}
+
+// Lists the boxes that hold lenses, the same way the puzzle shows them after each step
+impl fmt::Display for LensLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, lenses) in self.boxes.iter().enumerate().filter(|(_, lenses)| !lenses.is_empty()) {
+            let lenses: Vec<String> = lenses.iter().map(|lens| format!("[{} {}]", lens.label, lens.focal_length)).collect();
+            writeln!(f, "Box {}: {}", i, lenses.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Library contents after each step of the initialization sequence
+fn replay<'a>(steps: &[Step<'a>]) -> Vec<LensLibrary> {
+    let mut library = LensLibrary::new();
+
+    steps.iter().map(|step| {
+        library.apply(step);
+        library.clone()
+    }).collect()
 }
 
 fn solve_part_1(input: &str) -> u64 {
     input.split(',').filter(|&s| s != "").map(hash).sum()    
 }
 
-fn solve_part_2(input: &str) -> u64 {
-    let mut hashmap = HashMap::<u64, Vec<(String, u64)>>::new();
+fn solve_part_2(input: &str) -> Result<u64, StepError> {
+    let steps = parse_sequence(input)?;
 
-    input.split(',').filter(|&s| s != "").for_each(|s| configure_lens(s, &mut hashmap));
+    let mut library = LensLibrary::new();
+    steps.iter().for_each(|step| library.apply(step));
 
-    hashmap.iter().map(|(k, v)| v.iter().enumerate().fold(0, |acc, (i, lens)| acc + ((k + 1) * (i + 1) as u64 * lens.1))).sum()
+    Ok(library.focusing_power())
 }
 
 pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+pub fn print_history() {
     let input = include_str!("input.txt").trim();
     // let input = include_str!("test.txt").trim();
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    let steps = match parse_sequence(input) {
+        Ok(steps) => steps,
+        Err(e) => {
+            eprintln!("[ERROR] {}", e);
+            return;
+        }
+    };
+
+    for (step, library) in input.split(',').filter(|s| !s.is_empty()).zip(replay(&steps)) {
+        println!("After {:?}:\n{}", step, library);
+    }
 }
@@ -1,7 +1,15 @@
 use std::{collections::{BinaryHeap, HashSet}, cmp::Ordering};
 
+use crate::utils::input::{check_input, InputSpec};
+
 // https://adventofcode.com/2023/day/17
 
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| c.is_ascii_digit(),
+};
+
 type Position = (usize, usize);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -144,8 +152,10 @@ fn solve_part_2(input: &str) -> u64 {
 }
 
 pub fn answers() {
-    // let input = include_str!("input.txt").trim();
-    let input = include_str!("test.txt").trim();
+    // let input = include_str!("input.txt");
+    let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
 
     println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
     println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
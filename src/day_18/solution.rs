@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/18
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_alphanumeric() || " (#)".contains(c),
+};
+
+#[derive(Debug, Copy, Clone)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn offset(&self) -> (i64, i64) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+            Self::Right => (0, 1),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Instruction {
+    direction: Direction,
+    distance: i64,
+}
+
+#[derive(Debug)]
+enum InstructionError {
+    UnknownDirection(String),
+    InvalidDistance(String),
+    InvalidColour(String),
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownDirection(line) => write!(f, "Expected a direction of U, D, L or R in: {}", line),
+            Self::InvalidDistance(line) => write!(f, "Expected a distance in: {}", line),
+            Self::InvalidColour(line) => write!(f, "Expected a colour like (#70c710) in: {}", line),
+        }
+    }
+}
+
+// Reads the direction and distance written out in the plan
+fn parse_plain(line: &str) -> Result<Instruction, InstructionError> {
+    let mut fields = line.split_whitespace();
+
+    let direction = match fields.next() {
+        Some("U") => Direction::Up,
+        Some("D") => Direction::Down,
+        Some("L") => Direction::Left,
+        Some("R") => Direction::Right,
+        _ => return Err(InstructionError::UnknownDirection(line.to_owned())),
+    };
+
+    let distance = fields.next()
+        .and_then(|distance| distance.parse::<i64>().ok())
+        .ok_or_else(|| InstructionError::InvalidDistance(line.to_owned()))?;
+
+    Ok(Instruction { direction, distance })
+}
+
+// Reads the instruction hidden in the colour: five hex digits of distance followed by one of direction
+fn parse_colour(line: &str) -> Result<Instruction, InstructionError> {
+    let colour = line.split_whitespace().nth(2)
+        .and_then(|colour| colour.strip_prefix("(#"))
+        .and_then(|colour| colour.strip_suffix(')'))
+        .filter(|colour| colour.len() == 6)
+        .ok_or_else(|| InstructionError::InvalidColour(line.to_owned()))?;
+
+    let distance = i64::from_str_radix(&colour[..5], 16).map_err(|_| InstructionError::InvalidColour(line.to_owned()))?;
+
+    let direction = match &colour[5..] {
+        "0" => Direction::Right,
+        "1" => Direction::Down,
+        "2" => Direction::Left,
+        "3" => Direction::Up,
+        _ => return Err(InstructionError::InvalidColour(line.to_owned())),
+    };
+
+    Ok(Instruction { direction, distance })
+}
+
+// Cubic metres inside and on the dug trench. The shoelace formula gives the area enclosed by the
+// centres of the trench's squares, and Pick's theorem turns that into the number of interior
+// squares, to which the squares of the trench itself are added. None when the trench is not closed.
+fn lagoon_volume(instructions: &[Instruction]) -> Option<u64> {
+    let (mut row, mut col) = (0i64, 0i64);
+    let mut twice_area: i64 = 0;
+    let mut perimeter: i64 = 0;
+
+    for instruction in instructions {
+        let (dr, dc) = instruction.direction.offset();
+        let (next_row, next_col) = (row + dr * instruction.distance, col + dc * instruction.distance);
+
+        twice_area += col * next_row - next_col * row;
+        perimeter += instruction.distance;
+        (row, col) = (next_row, next_col);
+    }
+
+    if (row, col) != (0, 0) {
+        return None;
+    }
+
+    Some((twice_area.unsigned_abs() + perimeter as u64) / 2 + 1)
+}
+
+fn solve(input: &str, parse: fn(&str) -> Result<Instruction, InstructionError>) -> Result<u64, String> {
+    let instructions = input.lines().map(parse).collect::<Result<Vec<Instruction>, InstructionError>>().map_err(|e| e.to_string())?;
+
+    lagoon_volume(&instructions).ok_or_else(|| String::from("The trench does not return to where it started"))
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    solve(input, parse_plain)
+}
+
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    solve(input, parse_colour)
+}
+
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(62));
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), Ok(952408144115));
+    }
+}
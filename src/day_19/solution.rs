@@ -1,29 +1,126 @@
-use std::{cmp::Ordering, collections::{HashMap, VecDeque}};
+use std::{collections::{HashMap, HashSet, VecDeque}, fmt};
 
-use regex::Regex;
-use once_cell::sync::Lazy;
+use crate::utils::graph::Digraph;
+use crate::utils::interval::{Interval, IntervalBox, IntervalSet};
+use crate::utils::parse::{ParseResult, Parser};
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
 
 // https://adventofcode.com/2023/day/19
 
-static WORKFLOW_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?<name>[a-z]+)\{(?<rules>.+)\}$").unwrap());
-static RULE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:(?<category>[a-z]+)(?<ordering>[<>=])(?<rating>[0-9]+):)?(?<next>[a-z|A|R]+)").unwrap());
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_alphanumeric() || "{}<>=:,".contains(c),
+};
 
 type WorkflowName = String;
 type WorkflowRules = Vec<Rule>;
 type Workflows = HashMap<WorkflowName, WorkflowRules>;
 type Category = String;
+// Category names in the order their ratings are stored in parts and rating ranges
+type Categories = Vec<Category>;
 type Rating = u64;
-type Part = HashMap<Category, Rating>;
-type RatingRanges = HashMap<Category, (u64, u64)>;
+type Part = Vec<Rating>;
+// Ratings each category can still take, one side per category
+type RatingRanges = IntervalBox<Rating>;
 
-#[derive(Debug)]
+// Ratings a category can take when none are given
+fn default_bounds() -> Interval<Rating> {
+    Interval::closed(1, 4000).unwrap()
+}
+
+// Longer symbols first so that "<=" is not read as "<"
+const COMPARISON_SYMBOLS: [&str; 5] = ["<=", ">=", "<", ">", "="];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Comparison {
+    Less,
+    LessEqual,
+    Equal,
+    GreaterEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessEqual),
+            "=" => Some(Self::Equal),
+            ">=" => Some(Self::GreaterEqual),
+            ">" => Some(Self::Greater),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Equal => "=",
+            Self::GreaterEqual => ">=",
+            Self::Greater => ">",
+        }
+    }
+
+    fn matches(&self, value: Rating, rating: Rating) -> bool {
+        match self {
+            Self::Less => value < rating,
+            Self::LessEqual => value <= rating,
+            Self::Equal => value == rating,
+            Self::GreaterEqual => value >= rating,
+            Self::Greater => value > rating,
+        }
+    }
+
+    // Values that match, or None when no value can
+    fn matching_range(&self, rating: Rating) -> Option<Interval<Rating>> {
+        match self {
+            Self::Less => Interval::half_open(Rating::MIN, rating),
+            Self::LessEqual => Interval::closed(Rating::MIN, rating),
+            Self::Equal => Interval::closed(rating, rating),
+            Self::GreaterEqual => Interval::closed(rating, Rating::MAX),
+            Self::Greater => Interval::closed(rating.checked_add(1)?, Rating::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 struct Rule {
     category: Category,
-    ordering: Ordering,
+    comparison: Comparison,
     rating: Rating,
     next_workflow: WorkflowName,
 }
 
+impl Rule {
+    fn is_unconditional(&self) -> bool {
+        self.category.is_empty()
+    }
+
+    fn condition(&self) -> String {
+        format!("{}{}{}", self.category, self.comparison.symbol(), self.rating)
+    }
+
+    // Splits rating ranges into the part that matches this rule, if any, and the pieces that do not
+    fn split(&self, ranges: &RatingRanges, categories: &Categories) -> (Option<RatingRanges>, Vec<RatingRanges>) {
+        if self.is_unconditional() {
+            return (Some(ranges.clone()), Vec::new());
+        }
+
+        let index = categories.iter().position(|c| *c == self.category).unwrap();
+        let side = IntervalSet::from(ranges.side(index));
+        let matching: IntervalSet<Rating> = self.comparison.matching_range(self.rating).into_iter().collect();
+
+        // A single comparison matches one run of ratings, so at most one piece matches
+        let matched = side.intersection(&matching).iter().next().map(|&range| ranges.with_side(index, range));
+        let unmatched = side.difference(&matching).iter().map(|&range| ranges.with_side(index, range)).collect();
+
+        (matched, unmatched)
+    }
+}
+
 #[derive(Debug)]
 struct State {
     workflow: WorkflowName,
@@ -31,124 +128,223 @@ struct State {
 }
 
 impl State {
-    fn init() -> Self {
+    fn init(categories: &Categories, bounds: Interval<Rating>) -> Self {
         Self {
             workflow: String::from("in"),
-            ranges: HashMap::from([
-                (String::from("x"), (1, 4000)),
-                (String::from("m"), (1, 4000)),
-                (String::from("a"), (1, 4000)),
-                (String::from("s"), (1, 4000)),
-            ]),
+            ranges: IntervalBox::new(vec![bounds; categories.len()]),
         } 
     }
 }
 
-fn parse_workflow(workflow: &str, workflows: &mut Workflows) {
-    let workflow_captures = WORKFLOW_RE.captures(workflow).unwrap();
+// Workflow names, categories and targets, including A and R
+fn name<'a>(parser: &mut Parser<'a>) -> ParseResult<&'a str> {
+    parser.take_while("a name", |c| c.is_ascii_alphabetic())
+}
 
-    let workflow_name = &workflow_captures["name"];
-    let rules = &workflow_captures["rules"];
+// Either <category><comparison><rating>:<next workflow> or just <next workflow>
+fn parse_rule(parser: &mut Parser) -> ParseResult<Rule> {
+    // A name followed by a comparison is the category being checked, otherwise it is where parts go
+    let first = name(parser)?;
+
+    let Ok(symbol) = parser.one_of(&COMPARISON_SYMBOLS) else {
+        return Ok(Rule {
+            category: String::new(),
+            comparison: Comparison::Equal,
+            rating: 0,
+            next_workflow: first.to_owned(),
+        });
+    };
+
+    let rating = parser.unsigned()?;
+    parser.tag(":")?;
+    let next_workflow = name(parser)?;
+
+    Ok(Rule {
+        category: first.to_owned(),
+        comparison: Comparison::from_symbol(symbol).unwrap(),
+        rating,
+        next_workflow: next_workflow.to_owned(),
+    })
+}
 
-    rules.split(',').for_each(|r| {
-        let rule_captures = RULE_RE.captures(r).unwrap();
+// <name>{<rule>,<rule>,...}
+fn parse_workflow(workflow: &str, workflows: &mut Workflows) -> ParseResult<()> {
+    let mut parser = Parser::new(workflow);
 
-        let category = {
-            match rule_captures.name("category") {
-                Some(s) => s.as_str().to_owned(),
-                None => "".to_owned(),
-            }
-        };
-        let ordering = {
-            let symbol = rule_captures.name("ordering");
-            match symbol {
-                Some(s) => {
-                    match s.as_str() {
-                        "<" => Ordering::Less,
-                        ">" => Ordering::Greater,
-                        "=" => Ordering::Equal,
-                        _ => panic!("Unrecognized ordering symbol {}", s.as_str()),
-                    }
-                }
-                None => Ordering::Equal,
-            }
-        };
-        let rating = {
-            match rule_captures.name("rating") {
-                Some(s) => s.as_str().parse::<Rating>().unwrap(),
-                None => 0,
-            }
-        };
-        let next_workflow = rule_captures["next"].to_owned();
+    let workflow_name = name(&mut parser)?;
+    parser.tag("{")?;
+    let rules = parser.separated(",", parse_rule)?;
+    parser.tag("}")?;
+    parser.end()?;
 
-        let rule = Rule {
-            category,
-            ordering,
-            rating,
-            next_workflow,
-        };
+    workflows.entry(workflow_name.to_owned()).or_default().extend(rules);
+    Ok(())
+}
 
-        workflows.entry(workflow_name.to_owned()).or_insert_with(Vec::new).push(rule);
+fn parse_workflows(workflows: &str) -> Result<Workflows, String> {
+    let mut parsed: Workflows = HashMap::new();
+    for workflow in workflows.lines() {
+        parse_workflow(workflow, &mut parsed).map_err(|e| format!("{} in workflow: {}", e, workflow))?;
+    }
 
-    });
+    Ok(parsed)
 }
 
-fn parse_part(part: &str) -> Part {
-    let part_ratings = part.trim_start_matches('{').trim_end_matches('}');
-    let mut part: Part = HashMap::new();
+// Categories in the order they first appear in the parts, followed by any that only the workflows check
+fn collect_categories(workflows: &Workflows, parts: &str) -> Categories {
+    let mut categories = Categories::new();
 
-    for x in part_ratings.split(',') {
-        let (category, rating) = {
-            let mut x = x.split('=');
-            (x.next().unwrap().to_owned(), x.next().unwrap().parse::<u64>().unwrap())
-        };
+    for x in parts.lines().flat_map(|part| part.trim_start_matches('{').trim_end_matches('}').split(',')) {
+        let category = x.split('=').next().unwrap();
+        if !category.is_empty() && !categories.iter().any(|c| c == category) {
+            categories.push(category.to_owned());
+        }
+    }
+
+    let mut checked: Vec<&Category> = workflows.values().flatten().filter(|r| !r.is_unconditional()).map(|r| &r.category).collect();
+    checked.sort();
+    checked.dedup();
 
-        part.insert(category, rating);
+    for category in checked {
+        if !categories.contains(category) {
+            categories.push(category.clone());
+        }
     }
 
-    part
+    categories
 }
 
-fn check_rule(part: &Part, rule: &Rule) -> bool {
-    if rule.category == "" {
-        return true;
+// {<category>=<rating>,...}, where the braces may be left out
+fn parse_part(part: &str, categories: &Categories) -> Result<Part, String> {
+    let mut parser = Parser::new(part);
+    let mut ratings: Vec<Option<Rating>> = vec![None; categories.len()];
+
+    parser.try_tag("{");
+    let pairs = parser.key_values(",", "=", name, Parser::unsigned::<Rating>).map_err(|e| format!("{} in part {}", e, part))?;
+    parser.try_tag("}");
+    parser.end().map_err(|e| format!("{} in part {}", e, part))?;
+
+    for (category, rating) in pairs {
+        match categories.iter().position(|c| c == category) {
+            Some(index) => ratings[index] = Some(rating),
+            None => return Err(format!("Unrecognized category {}", category)),
+        }
     }
 
-    let part_rating = part.get(&rule.category).unwrap();
+    categories.iter().zip(ratings).map(|(category, rating)| {
+        rating.ok_or_else(|| format!("Part {} has no rating for {}", part, category))
+    }).collect()
+}
 
-    part_rating.cmp(&rule.rating) == rule.ordering
+// Where a rule sends a part: another workflow, by its index in `Program::entries`, or a verdict
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Target {
+    Workflow(usize),
+    Accept,
+    Reject,
 }
 
-fn check_part(part: &Part, workflows: &Workflows) -> bool {
-    let mut workflow_name = "in";
+#[derive(Debug, Copy, Clone)]
+enum Instruction {
+    // Jumps to the target when the part's rating in the category passes the comparison with the
+    // rating, otherwise falls through to the next instruction
+    Branch { category: usize, comparison: Comparison, rating: Rating, target: Target },
+    Jump(Target),
+}
 
-    loop {
-        if workflow_name == "A" {
-            return true;
-        }
+// Workflows flattened into one instruction list, with every name and category resolved up front
+#[derive(Debug)]
+struct Program {
+    instructions: Vec<Instruction>,
+    // Index of the first instruction of each workflow
+    entries: Vec<usize>,
+    start: Target,
+}
 
-        if workflow_name == "R" {
-            return false;
-        }
+impl Program {
+    fn compile(workflows: &Workflows, categories: &Categories) -> Result<Self, Diagnostic> {
+        let mut names: Vec<&WorkflowName> = workflows.keys().collect();
+        names.sort();
+
+        let ids: HashMap<&str, usize> = names.iter().enumerate().map(|(id, name)| (name.as_str(), id)).collect();
+        let resolve = |workflow: &WorkflowName, rule: usize, target: &WorkflowName| -> Result<Target, Diagnostic> {
+            match target.as_str() {
+                "A" => Ok(Target::Accept),
+                "R" => Ok(Target::Reject),
+                name => match ids.get(name) {
+                    Some(&id) => Ok(Target::Workflow(id)),
+                    None => Err(Diagnostic::UndefinedTarget { workflow: workflow.clone(), rule, target: target.clone() }),
+                },
+            }
+        };
+
+        let mut instructions = Vec::new();
+        let mut entries = Vec::with_capacity(names.len());
+
+        for &name in &names {
+            let rules = workflows.get(name).unwrap();
+            entries.push(instructions.len());
+
+            for (i, rule) in rules.iter().enumerate() {
+                let target = resolve(name, i, &rule.next_workflow)?;
 
-        let rules = workflows.get(workflow_name).unwrap();
+                if rule.is_unconditional() {
+                    instructions.push(Instruction::Jump(target));
+                } else {
+                    let category = match categories.iter().position(|c| *c == rule.category) {
+                        Some(category) => category,
+                        None => return Err(Diagnostic::UnknownCategory { workflow: name.clone(), rule: i, category: rule.category.clone() }),
+                    };
 
-        for rule in rules {
-            if check_rule(part, &rule) {
-                workflow_name = &rule.next_workflow;
-                break;
+                    instructions.push(Instruction::Branch { category, comparison: rule.comparison, rating: rule.rating, target });
+                }
+            }
+
+            if !matches!(instructions.last(), Some(Instruction::Jump(_))) {
+                return Err(Diagnostic::MissingFallback { workflow: name.clone() });
             }
         }
+
+        let start = match ids.get("in") {
+            Some(&id) => Target::Workflow(id),
+            None => return Err(Diagnostic::MissingStart),
+        };
+
+        Ok(Self {
+            instructions,
+            entries,
+            start,
+        })
+    }
+
+    fn evaluate(&self, part: &Part) -> bool {
+        let mut target = self.start;
+
+        loop {
+            let mut pc = match target {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(id) => self.entries[id],
+            };
+
+            target = loop {
+                match self.instructions[pc] {
+                    Instruction::Branch { category, comparison, rating, target } if comparison.matches(part[category], rating) => break target,
+                    Instruction::Branch { .. } => pc += 1,
+                    Instruction::Jump(target) => break target,
+                }
+            };
+        }
     }
 }
 
-fn count_accepted_rating_combinations(workflows: &Workflows) -> u64 {
+fn count_accepted_rating_combinations(workflows: &Workflows, categories: &Categories, bounds: Interval<Rating>) -> u128 {
     let mut combinations = 0;
-    let mut queue = VecDeque::from([State::init()]);
+    let mut queue = VecDeque::from([State::init(categories, bounds)]);
     
-    while let Some(State { workflow, mut ranges }) = queue.pop_front() {
+    while let Some(State { workflow, ranges }) = queue.pop_front() {
         if workflow == "A" {
-            combinations += ranges.values().map(|r| r.1 - r.0 + 1).product::<u64>();
+            combinations += ranges.volume();
             continue;
         }
 
@@ -156,72 +352,514 @@ fn count_accepted_rating_combinations(workflows: &Workflows) -> u64 {
             continue;
         } 
 
-        let rules = workflows.get(&workflow).unwrap();
-        for Rule { category, ordering, rating, next_workflow } in rules {
-            if category == "" {
-                queue.push_back(State {
-                    workflow: next_workflow.clone(),
-                    ranges: ranges.clone(),
-                })
-            } else {
-                let mut _ranges = ranges.clone();
-                let range = _ranges.get(category).unwrap();
-                let _range = match ordering {
-                    Ordering::Less if range.0 < *rating => {
-                        if range.1 >= *rating {
-                            ranges.insert(category.clone(), (*rating, range.1));
-                        }
-
-                        (range.0, range.1.min(*rating - 1))
-                    }
-                    Ordering::Greater if range.1 > *rating => {
-                        if range.0 <= *rating {
-                            ranges.insert(category.clone(), (range.0, *rating));
-                        }
-
-                        (range.0.max(*rating + 1), range.1)
-                    }
-                    _ => (0, 0),
-                };
-
-                if _range != (0, 0) {
-                    _ranges.insert(category.clone(), _range);
+        // Ratings that fail a rule move on to the next rule of the workflow
+        let mut remaining = vec![ranges];
+        for rule in workflows.get(&workflow).unwrap() {
+            let mut unmatched = Vec::new();
+
+            for ranges in remaining {
+                let (matched, rest) = rule.split(&ranges, categories);
+
+                if let Some(ranges) = matched {
                     queue.push_back(State {
-                        workflow: next_workflow.clone(),
-                        ranges: _ranges,
-                    })
+                        workflow: rule.next_workflow.clone(),
+                        ranges,
+                    });
                 }
+
+                unmatched.extend(rest);
             }
+
+            remaining = unmatched;
         }
     }
 
     combinations
 }
 
-fn solve_part_1(input: &str) -> u64 {
+// Problems found in the workflows, either by analyze_workflows or while compiling them
+#[derive(Debug)]
+enum Diagnostic {
+    MissingStart,
+    // Workflows that can send parts back to themselves, so evaluation may never finish
+    Cycle { workflows: Vec<WorkflowName> },
+    UndefinedTarget { workflow: WorkflowName, rule: usize, target: WorkflowName },
+    UnknownCategory { workflow: WorkflowName, rule: usize, category: Category },
+    // The last rule is conditional, so a part could run off the end of the workflow
+    MissingFallback { workflow: WorkflowName },
+    // Every part reaching this rule was already sent elsewhere by an earlier rule
+    UnreachableRule { workflow: WorkflowName, rule: usize },
+    UnreachableWorkflow { workflow: WorkflowName },
+}
+
+impl Diagnostic {
+    // Errors make evaluation loop forever or fail, while the rest only point at dead rules
+    fn is_error(&self) -> bool {
+        !matches!(self, Self::UnreachableRule { .. } | Self::UnreachableWorkflow { .. })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingStart => write!(f, "No workflow named in"),
+            Self::Cycle { workflows } => write!(f, "Workflows form a cycle: {} -> {}", workflows.join(" -> "), workflows[0]),
+            Self::UndefinedTarget { workflow, rule, target } => write!(f, "Rule {} of {} sends parts to undefined workflow {}", rule, workflow, target),
+            Self::UnknownCategory { workflow, rule, category } => write!(f, "Rule {} of {} checks unknown category {}", rule, workflow, category),
+            Self::MissingFallback { workflow } => write!(f, "Workflow {} does not end with an unconditional rule", workflow),
+            Self::UnreachableRule { workflow, rule } => write!(f, "Rule {} of {} can never match", rule, workflow),
+            Self::UnreachableWorkflow { workflow } => write!(f, "Workflow {} is unreachable from in", workflow),
+        }
+    }
+}
+
+fn analyze_workflows(workflows: &Workflows) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut names: Vec<&WorkflowName> = workflows.keys().collect();
+    names.sort();
+
+    // Dangling targets, missing fallbacks and rules shadowed by the rules before them
+    let categories = collect_categories(workflows, "");
+    for &name in &names {
+        let rules = workflows.get(name).unwrap();
+        let mut remaining = vec![State::init(&categories, default_bounds()).ranges];
+
+        if !rules.last().is_some_and(|rule| rule.is_unconditional()) {
+            diagnostics.push(Diagnostic::MissingFallback { workflow: name.clone() });
+        }
+
+        for (i, rule) in rules.iter().enumerate() {
+            if !["A", "R"].contains(&rule.next_workflow.as_str()) && !workflows.contains_key(&rule.next_workflow) {
+                diagnostics.push(Diagnostic::UndefinedTarget { workflow: name.clone(), rule: i, target: rule.next_workflow.clone() });
+            }
+
+            let mut fires = false;
+            let mut unmatched = Vec::new();
+
+            for ranges in &remaining {
+                let (matched, rest) = rule.split(ranges, &categories);
+                fires |= matched.is_some();
+                unmatched.extend(rest);
+            }
+
+            if !fires {
+                diagnostics.push(Diagnostic::UnreachableRule { workflow: name.clone(), rule: i });
+            }
+
+            remaining = unmatched;
+        }
+    }
+
+    // Workflows that no path from in leads to
+    if workflows.contains_key("in") {
+        let mut reachable = HashSet::<&str>::from(["in"]);
+        let mut queue = VecDeque::from(["in"]);
+
+        while let Some(name) = queue.pop_front() {
+            for rule in workflows.get(name).into_iter().flatten() {
+                if reachable.insert(&rule.next_workflow) {
+                    queue.push_back(&rule.next_workflow);
+                }
+            }
+        }
+
+        for &name in &names {
+            if !reachable.contains(name.as_str()) {
+                diagnostics.push(Diagnostic::UnreachableWorkflow { workflow: name.clone() });
+            }
+        }
+    } else {
+        diagnostics.push(Diagnostic::MissingStart);
+    }
+
+    // Cycles, found as the back edges of a depth-first search over the workflows
+    fn visit<'a>(name: &'a str, workflows: &'a Workflows, path: &mut Vec<&'a str>, finished: &mut HashSet<&'a str>, diagnostics: &mut Vec<Diagnostic>) {
+        path.push(name);
+
+        for rule in workflows.get(name).into_iter().flatten() {
+            let next = rule.next_workflow.as_str();
+
+            if let Some(start) = path.iter().position(|&n| n == next) {
+                let workflows = path[start..].iter().map(|&n| n.to_owned()).collect();
+                diagnostics.push(Diagnostic::Cycle { workflows });
+            } else if workflows.contains_key(next) && !finished.contains(next) {
+                visit(next, workflows, path, finished, diagnostics);
+            }
+        }
+
+        path.pop();
+        finished.insert(name);
+    }
+
+    let mut finished = HashSet::<&str>::new();
+    for &name in &names {
+        if !finished.contains(name.as_str()) {
+            visit(name, workflows, &mut Vec::new(), &mut finished, &mut diagnostics);
+        }
+    }
+
+    diagnostics
+}
+
+// The first diagnostic that is an error, if any. analyze() lists all of them.
+fn check_workflows(workflows: &Workflows) -> Result<(), Diagnostic> {
+    match analyze_workflows(workflows).into_iter().find(|d| d.is_error()) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+// Why a conditional rule matched: the part's rating in the category passed the comparison
+#[derive(Debug)]
+struct Check {
+    category: Category,
+    value: Rating,
+    comparison: Comparison,
+    rating: Rating,
+}
+
+#[derive(Debug)]
+struct TraceStep {
+    workflow: WorkflowName,
+    rule: usize,
+    // None when the matching rule was unconditional
+    check: Option<Check>,
+    next_workflow: WorkflowName,
+}
+
+#[derive(Debug, PartialEq)]
+enum Verdict {
+    Accepted,
+    Rejected,
+    // The part came back to a workflow it had already been through, so it would never finish
+    Cycle,
+    UndefinedWorkflow(WorkflowName),
+    NoRuleMatched(WorkflowName),
+}
+
+#[derive(Debug)]
+struct Trace {
+    steps: Vec<TraceStep>,
+    verdict: Verdict,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            match &step.check {
+                Some(Check { category, value, comparison, rating }) => writeln!(f, "{}: rule {} matched since {}={} is {}{}, go to {}",
+                    step.workflow, step.rule, category, value, comparison.symbol(), rating, step.next_workflow)?,
+                None => writeln!(f, "{}: rule {} is unconditional, go to {}", step.workflow, step.rule, step.next_workflow)?,
+            }
+        }
+
+        match &self.verdict {
+            Verdict::Accepted => write!(f, "Accepted"),
+            Verdict::Rejected => write!(f, "Rejected"),
+            Verdict::Cycle => write!(f, "Never finishes, it is sent back to {}", self.steps.last().unwrap().next_workflow),
+            Verdict::UndefinedWorkflow(name) => write!(f, "Stuck, there is no workflow named {}", name),
+            Verdict::NoRuleMatched(name) => write!(f, "Stuck, no rule of {} matched", name),
+        }
+    }
+}
+
+fn trace_part(part: &Part, categories: &Categories, workflows: &Workflows) -> Trace {
+    let mut steps = Vec::new();
+    let mut visited = HashSet::<&str>::new();
+    let mut workflow_name = "in";
+
+    let verdict = loop {
+        match workflow_name {
+            "A" => break Verdict::Accepted,
+            "R" => break Verdict::Rejected,
+            _ => {}
+        }
+
+        if !visited.insert(workflow_name) {
+            break Verdict::Cycle;
+        }
+
+        let rules = match workflows.get(workflow_name) {
+            Some(rules) => rules,
+            None => break Verdict::UndefinedWorkflow(workflow_name.to_owned()),
+        };
+
+        let matched = rules.iter().enumerate().find_map(|(i, rule)| {
+            if rule.is_unconditional() {
+                return Some((i, rule, None));
+            }
+
+            let value = part[categories.iter().position(|c| *c == rule.category).unwrap()];
+            if rule.comparison.matches(value, rule.rating) {
+                Some((i, rule, Some(Check { category: rule.category.clone(), value, comparison: rule.comparison, rating: rule.rating })))
+            } else {
+                None
+            }
+        });
+
+        let (i, rule, check) = match matched {
+            Some(matched) => matched,
+            None => break Verdict::NoRuleMatched(workflow_name.to_owned()),
+        };
+
+        steps.push(TraceStep {
+            workflow: workflow_name.to_owned(),
+            rule: i,
+            check,
+            next_workflow: rule.next_workflow.clone(),
+        });
+
+        workflow_name = &rule.next_workflow;
+    };
+
+    Trace {
+        steps,
+        verdict,
+    }
+}
+
+fn format_workflows(workflows: &Workflows) -> String {
+    let mut names: Vec<&WorkflowName> = workflows.keys().collect();
+    names.sort_by_key(|&name| (name != "in", name));
+
+    names.iter().map(|&name| {
+        let rules: Vec<String> = workflows.get(name).unwrap().iter().map(|rule| {
+            if rule.is_unconditional() {
+                rule.next_workflow.clone()
+            } else {
+                format!("{}:{}", rule.condition(), rule.next_workflow)
+            }
+        }).collect();
+
+        format!("{}{{{}}}", name, rules.join(","))
+    }).collect::<Vec<String>>().join("\n")
+}
+
+// Rewrites the workflows into an equivalent, smaller set. Expects workflows without cycles or
+// undefined targets, since it follows every rule.
+fn simplify_workflows(workflows: &Workflows) -> Workflows {
+    let mut workflows = workflows.clone();
+
+    loop {
+        let mut changed = false;
+
+        // Drop rules after the first unconditional one, and conditional rules that go to the
+        // same place as the fallback right after them
+        for rules in workflows.values_mut() {
+            if let Some(end) = rules.iter().position(|rule| rule.is_unconditional()) {
+                changed |= end + 1 < rules.len();
+                rules.truncate(end + 1);
+            }
+
+            while rules.len() >= 2 && rules[rules.len() - 2].next_workflow == rules[rules.len() - 1].next_workflow {
+                rules.remove(rules.len() - 2);
+                changed = true;
+            }
+        }
+
+        // Workflows that always send parts to the same place can be skipped by their callers
+        let redirects: HashMap<WorkflowName, WorkflowName> = workflows.iter()
+            .filter(|(name, rules)| *name != "in" && rules.len() == 1 && rules[0].is_unconditional())
+            .map(|(name, rules)| (name.clone(), rules[0].next_workflow.clone()))
+            .collect();
+
+        for rule in workflows.values_mut().flatten() {
+            if let Some(next) = redirects.get(&rule.next_workflow) {
+                rule.next_workflow = next.clone();
+                changed = true;
+            }
+        }
+
+        // A workflow used only as another workflow's fallback can replace that fallback
+        let mut uses = HashMap::<&WorkflowName, usize>::new();
+        for rule in workflows.values().flatten() {
+            *uses.entry(&rule.next_workflow).or_insert(0) += 1;
+        }
+
+        let inline = workflows.iter().find_map(|(name, rules)| {
+            let fallback = &rules.last().unwrap().next_workflow;
+            let is_single_use = fallback != name && fallback != "in" && uses.get(fallback) == Some(&1);
+
+            if is_single_use && workflows.contains_key(fallback) {
+                Some((name.clone(), fallback.clone()))
+            } else {
+                None
+            }
+        });
+
+        if let Some((caller, callee)) = inline {
+            let inlined = workflows.remove(&callee).unwrap();
+            let rules = workflows.get_mut(&caller).unwrap();
+            rules.pop();
+            rules.extend(inlined);
+            changed = true;
+        }
+
+        // Finally drop anything that is no longer reachable from in
+        let mut reachable = HashSet::<WorkflowName>::from([String::from("in")]);
+        let mut queue = VecDeque::from([String::from("in")]);
+        while let Some(name) = queue.pop_front() {
+            for rule in workflows.get(&name).into_iter().flatten() {
+                if reachable.insert(rule.next_workflow.clone()) {
+                    queue.push_back(rule.next_workflow.clone());
+                }
+            }
+        }
+
+        let num_workflows = workflows.len();
+        workflows.retain(|name, _| reachable.contains(name));
+        changed |= workflows.len() != num_workflows;
+
+        if !changed {
+            return workflows;
+        }
+    }
+}
+
+fn workflows_to_dot(workflows: &Workflows) -> String {
+    let mut graph = Digraph::new("workflows");
+
+    graph.node("in", &[("shape", "doublecircle")]);
+    graph.node("A", &[("shape", "box"), ("style", "filled"), ("fillcolor", "palegreen")]);
+    graph.node("R", &[("shape", "box"), ("style", "filled"), ("fillcolor", "lightcoral")]);
+
+    let mut names: Vec<&WorkflowName> = workflows.keys().collect();
+    names.sort();
+
+    for name in names {
+        for rule in workflows.get(name).unwrap() {
+            let label = if rule.is_unconditional() { String::from("else") } else { rule.condition() };
+            graph.edge(name, &rule.next_workflow, &[("label", &label)]);
+        }
+    }
+
+    graph.to_dot()
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
     let mut input = input.split("\n\n");
-    
-    let mut workflows: Workflows = HashMap::new();
-    input.next().unwrap().lines().for_each(|workflow| parse_workflow(workflow, &mut workflows));
 
-    let parts = input.next().unwrap().lines().map(parse_part);
+    let workflows = parse_workflows(input.next().unwrap())?;
+    check_workflows(&workflows).map_err(|e| e.to_string())?;
+
+    let parts = input.next().ok_or("Expected a blank line between the workflows and the parts")?;
+    let categories = collect_categories(&workflows, parts);
+
+    let program = Program::compile(&workflows, &categories).map_err(|e| e.to_string())?;
+    let parts = parts.lines().map(|part| parse_part(part, &categories)).collect::<Result<Vec<Part>, String>>()?;
 
-    parts.filter(|part| check_part(part, &workflows)).map(|part| part.values().sum::<Rating>()).sum::<u64>()
+    Ok(parts.iter().filter(|part| program.evaluate(part)).map(|part| part.iter().sum::<Rating>()).sum())
 }
 
-fn solve_part_2(input: &str) -> u64 {
+fn solve_part_2(input: &str) -> Result<u128, String> {
     let mut input = input.split("\n\n");
-    
-    let mut workflows: Workflows = HashMap::new();
-    input.next().unwrap().lines().for_each(|workflow| parse_workflow(workflow, &mut workflows));
 
-    count_accepted_rating_combinations(&workflows)
+    let workflows = parse_workflows(input.next().unwrap())?;
+    check_workflows(&workflows).map_err(|e| e.to_string())?;
+
+    let categories = collect_categories(&workflows, input.next().unwrap_or(""));
+
+    Ok(count_accepted_rating_combinations(&workflows, &categories, default_bounds()))
 }
 
 pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+pub fn export_dot() {
     let input = include_str!("input.txt").trim();
     // let input = include_str!("test.txt").trim();
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    let workflows = match parse_workflows(input.split("\n\n").next().unwrap()) {
+        Ok(workflows) => workflows,
+        Err(e) => {
+            eprintln!("[DOT :: ERROR] {}", e);
+            return;
+        }
+    };
+
+    print!("{}", workflows_to_dot(&workflows));
+}
+
+pub fn analyze() {
+    let input = include_str!("input.txt").trim();
+    // let input = include_str!("test.txt").trim();
+
+    let workflows = match parse_workflows(input.split("\n\n").next().unwrap()) {
+        Ok(workflows) => workflows,
+        Err(e) => {
+            eprintln!("[ANALYZE :: ERROR] {}", e);
+            return;
+        }
+    };
+
+    for diagnostic in analyze_workflows(&workflows) {
+        let level = if diagnostic.is_error() { "ERROR" } else { "WARN" };
+        println!("[{}] {}", level, diagnostic);
+    }
+}
+
+pub fn simplify() {
+    let input = include_str!("input.txt").trim();
+    // let input = include_str!("test.txt").trim();
+
+    let mut input = input.split("\n\n");
+    let workflows = match parse_workflows(input.next().unwrap()) {
+        Ok(workflows) => workflows,
+        Err(e) => {
+            eprintln!("[SIMPLIFY :: ERROR] {}", e);
+            return;
+        }
+    };
+    if let Err(e) = check_workflows(&workflows) {
+        eprintln!("[SIMPLIFY :: ERROR] {}", e);
+        return;
+    }
+
+    let categories = collect_categories(&workflows, input.next().unwrap_or(""));
+    let simplified = simplify_workflows(&workflows);
+
+    let before = count_accepted_rating_combinations(&workflows, &categories, default_bounds());
+    let after = count_accepted_rating_combinations(&simplified, &categories, default_bounds());
+    if before != after {
+        eprintln!("[SIMPLIFY :: ERROR] Simplified workflows accept {} combinations instead of {}", after, before);
+        return;
+    }
+
+    eprintln!("[SIMPLIFY :: INFO] Reduced {} workflows with {} rules to {} workflows with {} rules",
+        workflows.len(), workflows.values().flatten().count(), simplified.len(), simplified.values().flatten().count());
+    println!("{}", format_workflows(&simplified));
+}
+
+// Returns whether the part could be parsed and traced
+pub fn print_part_trace(part: &str) -> bool {
+    let input = include_str!("input.txt").trim();
+    // let input = include_str!("test.txt").trim();
+
+    let workflows = match parse_workflows(input.split("\n\n").next().unwrap()) {
+        Ok(workflows) => workflows,
+        Err(e) => {
+            eprintln!("[TRACE :: ERROR] {}", e);
+            return false;
+        }
+    };
+    let categories = collect_categories(&workflows, part);
+
+    match parse_part(part, &categories) {
+        Ok(ratings) => {
+            println!("{}", trace_part(&ratings, &categories, &workflows));
+            true
+        }
+        Err(e) => {
+            eprintln!("[TRACE :: ERROR] {}", e);
+            false
+        }
+    }
 }
@@ -1,100 +1,133 @@
-// use std::collections::HashMap;
+use std::{collections::{HashMap, HashSet}, fmt};
 
-use regex::Regex;
-use once_cell::sync::Lazy;
+use crate::utils::lines::sum_lines;
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
 
 // https://adventofcode.com/2023/day/2
 
-// const MAX_COUNTS: Lazy<HashMap<&str, u8>> = Lazy::new( || HashMap::from([
-//     ("red", 12),
-//     ("green", 13),
-//     ("blue", 14),
-// ]));
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: false,
+    allowed: |c| c.is_ascii_alphanumeric() || " :;,".contains(c),
+};
 
-static GAME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Game (?<id>\d+):\s(?<rounds>.*)$").unwrap());
-// static ROUND_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?<amount>\d+)\s(?<color>\w+)$").unwrap());
+type Colour = String;
 
-static RED_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?<amount>\d+)\sred").unwrap());
-static GREEN_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?<amount>\d+)\sgreen").unwrap());
-static BLUE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?<amount>\d+)\sblue").unwrap());
+// Number of cubes of each colour, where colours that are not listed have none
+type Cubes = HashMap<Colour, u64>;
 
-macro_rules! get_color_count {
-    ($re:ident, $hay:ident) => (
-        (match $re.captures($hay).and_then(|captures| captures.name("amount")) {
-            Some(value) => value.as_str().parse::<u64>().unwrap(),
-            None => 0, 
-        })
-    )
+// Cubes the elf is asked about in part 1
+fn part_1_bag() -> Cubes {
+    HashMap::from([
+        (String::from("red"), 12),
+        (String::from("green"), 13),
+        (String::from("blue"), 14),
+    ])
 }
 
-fn solve_part_1(input: &str) -> u64 {
-    fn check_rounds(rounds: &str) -> bool {
-        let rounds = rounds.split(';');
-
-        for round in rounds {
-            // for count in round.split(',') {
-            //     let captures = ROUND_RE.captures(count.trim()).unwrap();
-            //     let color = &captures["color"];
-            //     let amount = &captures["amount"].parse::<u8>().unwrap();
-            //
-            //     if MAX_COUNTS.get(color).unwrap() < amount{
-            //         return false;
-            //     }
-            // }
-            
-            let is_red_valid = get_color_count!(RED_RE, round) <= 12;
-            let is_green_valid = get_color_count!(GREEN_RE, round) <= 13;
-            let is_blue_valid = get_color_count!(BLUE_RE, round) <= 14;
-
-            if !is_red_valid || !is_blue_valid || !is_green_valid {
-                return false;
-            }
-        }
+// Product of the bag's counts of the given colours, so a colour the bag has none of makes it 0
+fn power(bag: &Cubes, colours: &HashSet<Colour>) -> u64 {
+    colours.iter().map(|colour| bag.get(colour).copied().unwrap_or(0)).product()
+}
 
-        true
-    }
+// Cubes revealed in one handful
+type Round = Cubes;
 
-    fn check_game(game: &str) -> u64 {
-        match GAME_RE.captures(game) {
-            Some(captures) if check_rounds(&captures["rounds"]) => *(&captures["id"].parse::<u64>().unwrap()),
-            _ => 0
+#[derive(Debug)]
+struct Game {
+    id: u64,
+    rounds: Vec<Round>,
+}
+
+#[derive(Debug)]
+enum GameError {
+    MissingHeader(String),
+    InvalidCount(String),
+    // A round that names the same colour twice, e.g. "3 blue, 4 blue"
+    DuplicateColour { round: usize, colour: String },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingHeader(line) => write!(f, "Expected \"Game <id>: \" at the start of: {}", line),
+            Self::InvalidCount(count) => write!(f, "Expected \"<amount> <colour>\" but found: {}", count),
+            Self::DuplicateColour { round, colour } => write!(f, "Round {} names {} more than once", round, colour),
         }
     }
-
-    input.lines().map(check_game).sum()
 }
 
-fn solve_part_2(input: &str) -> u64 {
-    fn check_rounds(rounds: &str) -> (u64, u64, u64) {
-        let rounds = rounds.split(';');
+impl Game {
+    fn parse(line: &str) -> Result<Self, GameError> {
+        let (id, rounds) = match line.strip_prefix("Game ").and_then(|rest| rest.split_once(": ")) {
+            Some((id, rounds)) => match id.parse::<u64>() {
+                Ok(id) => (id, rounds),
+                Err(_) => return Err(GameError::MissingHeader(line.to_owned())),
+            },
+            None => return Err(GameError::MissingHeader(line.to_owned())),
+        };
+
+        let rounds = rounds.split("; ").enumerate().map(|(i, round)| {
+            let mut cubes = Cubes::new();
+
+            for count in round.split(", ") {
+                let (amount, colour) = match count.split_once(' ').map(|(amount, colour)| (amount.parse::<u64>(), colour)) {
+                    Some((Ok(amount), colour)) if !colour.is_empty() && colour.chars().all(|c| c.is_ascii_alphabetic()) => (amount, colour),
+                    _ => return Err(GameError::InvalidCount(count.to_owned())),
+                };
+
+                if cubes.insert(colour.to_owned(), amount).is_some() {
+                    return Err(GameError::DuplicateColour { round: i, colour: colour.to_owned() });
+                }
+            }
 
-        let round_counts = rounds.map(|round| {
-            let red_count = get_color_count!(RED_RE, round);
-            let green_count = get_color_count!(GREEN_RE, round);
-            let blue_count = get_color_count!(BLUE_RE, round);
+            Ok(cubes)
+        }).collect::<Result<Vec<Round>, GameError>>()?;
 
-            (red_count, green_count, blue_count)
-        });
+        Ok(Self {
+            id,
+            rounds,
+        })
+    }
 
-        round_counts.fold((1u64, 1u64, 1u64), |acc, x| (acc.0.max(x.0), acc.1.max(x.1), acc.2.max(x.2)))
+    // Whether every round fits within the limits, where colours without a limit allow no cubes
+    fn is_possible(&self, limits: &HashMap<Colour, u64>) -> bool {
+        self.rounds.iter().flatten().all(|(colour, &amount)| amount <= *limits.get(colour).unwrap_or(&0))
     }
 
-    fn check_game(game: &str) -> u64 {
-        match GAME_RE.captures(game) {
-            Some(captures) => {
-                let counts = check_rounds(&captures["rounds"]);
-                (counts.0 * counts.1 * counts.2) as u64
-            }
-            _ => 0
+    // Fewest cubes of each colour that could have produced every round
+    fn minimum_bag(&self) -> Cubes {
+        let mut bag = Cubes::new();
+
+        for (colour, &amount) in self.rounds.iter().flatten() {
+            let n = bag.entry(colour.clone()).or_insert(0);
+            *n = (*n).max(amount);
         }
+
+        bag
     }
+}
+
+fn solve_part_1(input: &str) -> Result<u64, GameError> {
+    let bag = part_1_bag();
+
+    sum_lines(input, |line| Game::parse(line).map(|game| if game.is_possible(&bag) { game.id } else { 0 }))
+}
+
+// The power is taken over every colour seen anywhere in the input
+fn solve_part_2(input: &str) -> Result<u64, GameError> {
+    let games = input.lines().map(Game::parse).collect::<Result<Vec<Game>, GameError>>()?;
+    let colours: HashSet<Colour> = games.iter().flat_map(|game| game.rounds.iter().flat_map(|round| round.keys().cloned())).collect();
 
-    input.lines().map(check_game).sum()
+    Ok(games.iter().map(|game| power(&game.minimum_bag(), &colours)).sum())
 }
 
 pub fn answers() {
-    let input = include_str!("input.txt").trim();
+    let input = include_str!("input.txt");
+    check_input(input, None, &INPUT_SPEC);
+    let input = input.trim();
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
 }
@@ -0,0 +1,219 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::utils::math::lcm;
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/20
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 20,
+    grid: false,
+    allowed: |c| c.is_ascii_lowercase() || " %&->,".contains(c),
+};
+
+const BUTTON_PRESSES: usize = 1000;
+// Presses to wait for every input of rx's feeder to send a high pulse before giving up
+const MAX_PERIOD_PRESSES: u64 = 100_000;
+
+type ModuleId = usize;
+
+#[derive(Debug)]
+enum Kind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    // Named only as a destination, like "output" or "rx"
+    Sink,
+}
+
+#[derive(Debug)]
+struct Module {
+    kind: Kind,
+    inputs: Vec<ModuleId>,
+    outputs: Vec<ModuleId>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Pulse {
+    from: ModuleId,
+    to: ModuleId,
+    high: bool,
+}
+
+#[derive(Debug)]
+struct Network<'a> {
+    modules: Vec<Module>,
+    ids: HashMap<&'a str, ModuleId>,
+    broadcaster: ModuleId,
+}
+
+impl<'a> Network<'a> {
+    fn intern(modules: &mut Vec<Module>, ids: &mut HashMap<&'a str, ModuleId>, name: &'a str) -> ModuleId {
+        *ids.entry(name).or_insert_with(|| {
+            modules.push(Module { kind: Kind::Sink, inputs: Vec::new(), outputs: Vec::new() });
+            modules.len() - 1
+        })
+    }
+
+    fn parse(input: &'a str) -> Result<Self, String> {
+        let mut modules = Vec::<Module>::new();
+        let mut ids = HashMap::<&str, ModuleId>::new();
+
+        for line in input.lines() {
+            let (source, destinations) = line.split_once(" -> ").ok_or_else(|| format!("Expected \"<module> -> <destinations>\" but found: {}", line))?;
+
+            let (kind, name) = match source.as_bytes().first() {
+                Some(b'%') => (Kind::FlipFlop, &source[1..]),
+                Some(b'&') => (Kind::Conjunction, &source[1..]),
+                _ if source == "broadcaster" => (Kind::Broadcaster, source),
+                _ => return Err(format!("Unknown module type: {}", source)),
+            };
+
+            let id = Self::intern(&mut modules, &mut ids, name);
+            if !matches!(modules[id].kind, Kind::Sink) {
+                return Err(format!("Module {} is defined more than once", name));
+            }
+            modules[id].kind = kind;
+
+            for destination in destinations.split(", ") {
+                let to = Self::intern(&mut modules, &mut ids, destination);
+                modules[id].outputs.push(to);
+                modules[to].inputs.push(id);
+            }
+        }
+
+        let broadcaster = *ids.get("broadcaster").ok_or("No broadcaster module")?;
+
+        Ok(Self {
+            modules,
+            ids,
+            broadcaster,
+        })
+    }
+}
+
+// Flip-flop states and the last pulse each conjunction remembers from every input
+#[derive(Debug)]
+struct Machine<'n, 'a> {
+    network: &'n Network<'a>,
+    flip_flops: Vec<bool>,
+    memory: Vec<HashMap<ModuleId, bool>>,
+}
+
+impl<'n, 'a> Machine<'n, 'a> {
+    fn new(network: &'n Network<'a>) -> Self {
+        Self {
+            network,
+            flip_flops: vec![false; network.modules.len()],
+            memory: network.modules.iter().map(|module| module.inputs.iter().map(|&input| (input, false)).collect()).collect(),
+        }
+    }
+
+    // Pushes the button and calls `observe` with every pulse sent until the network is quiet again,
+    // including the low pulse from the button to the broadcaster
+    fn press_button(&mut self, mut observe: impl FnMut(&Pulse)) {
+        let button = self.network.modules.len();
+        let mut queue = VecDeque::from([Pulse { from: button, to: self.network.broadcaster, high: false }]);
+
+        while let Some(pulse) = queue.pop_front() {
+            observe(&pulse);
+
+            let module = &self.network.modules[pulse.to];
+            let high = match module.kind {
+                Kind::Broadcaster => pulse.high,
+                Kind::FlipFlop if pulse.high => continue,
+                Kind::FlipFlop => {
+                    self.flip_flops[pulse.to] = !self.flip_flops[pulse.to];
+                    self.flip_flops[pulse.to]
+                }
+                Kind::Conjunction => {
+                    let memory = &mut self.memory[pulse.to];
+                    memory.insert(pulse.from, pulse.high);
+                    !memory.values().all(|&high| high)
+                }
+                Kind::Sink => continue,
+            };
+
+            queue.extend(module.outputs.iter().map(|&to| Pulse { from: pulse.to, to, high }));
+        }
+    }
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    let network = Network::parse(input)?;
+
+    let mut machine = Machine::new(&network);
+    let (mut low, mut high) = (0, 0);
+
+    for _ in 0..BUTTON_PRESSES {
+        machine.press_button(|pulse| if pulse.high { high += 1 } else { low += 1 });
+    }
+
+    Ok(low * high)
+}
+
+// Assumes rx is fed by a single conjunction whose inputs are each driven by an independent counter
+// that sends one high pulse on every multiple of its period, as in the puzzle inputs. rx then first
+// sees a low pulse on the least common multiple of those periods.
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    let network = Network::parse(input)?;
+
+    let &rx = network.ids.get("rx").ok_or("No module sends pulses to rx")?;
+
+    let feeder = match network.modules[rx].inputs.as_slice() {
+        &[feeder] if matches!(network.modules[feeder].kind, Kind::Conjunction) => feeder,
+        _ => return Err(String::from("Expected rx to be fed by a single conjunction")),
+    };
+
+    let mut periods = HashMap::<ModuleId, u64>::new();
+    let mut machine = Machine::new(&network);
+    let mut presses = 0;
+
+    while periods.len() < network.modules[feeder].inputs.len() {
+        if presses == MAX_PERIOD_PRESSES {
+            let mut silent: Vec<&str> = network.ids.iter()
+                .filter(|&(_, id)| network.modules[feeder].inputs.contains(id) && !periods.contains_key(id))
+                .map(|(&name, _)| name)
+                .collect();
+            silent.sort_unstable();
+
+            return Err(format!("Inputs {:?} of the conjunction feeding rx sent no high pulse within {} presses", silent, MAX_PERIOD_PRESSES));
+        }
+
+        presses += 1;
+        machine.press_button(|pulse| if pulse.to == feeder && pulse.high {
+            periods.entry(pulse.from).or_insert(presses);
+        });
+    }
+
+    periods.values().try_fold(1, |acc, &period| lcm(acc, period)).ok_or_else(|| {
+        format!("The least common multiple of {:?} does not fit in a u64", periods.values().collect::<Vec<_>>())
+    })
+}
+
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1("broadcaster -> a, b, c\n%a -> b\n%b -> c\n%c -> inv\n&inv -> a"), Ok(32000000));
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(11687500));
+    }
+
+    #[test]
+    fn part_2_silent_input() {
+        assert!(solve_part_2("broadcaster -> a\n%a -> c\n&d -> c\n&c -> rx").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/21
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| ".#S".contains(c),
+};
+
+// How many whole repetitions of the map to look past before giving up on the reachable counts
+// settling into a quadratic
+const MAX_SETTLING_REPETITIONS: i64 = 8;
+
+type Position = (i64, i64);
+
+#[derive(Debug)]
+struct Garden<'a> {
+    tiles: Vec<&'a [u8]>,
+    start: Position,
+}
+
+impl<'a> Garden<'a> {
+    fn parse(input: &'a str) -> Option<Self> {
+        let tiles: Vec<&[u8]> = input.lines().map(|line| line.as_bytes()).collect();
+
+        let start = tiles.iter().enumerate().find_map(|(row, line)| {
+            line.iter().position(|&tile| tile == b'S').map(|col| (row as i64, col as i64))
+        })?;
+
+        Some(Self {
+            tiles,
+            start,
+        })
+    }
+
+    fn rows(&self) -> i64 {
+        self.tiles.len() as i64
+    }
+
+    fn cols(&self) -> i64 {
+        self.tiles[0].len() as i64
+    }
+
+    // Whether a position holds a garden plot, where a tiled garden repeats the map in every direction
+    fn is_plot(&self, (row, col): Position, tiled: bool) -> bool {
+        let (row, col) = if tiled {
+            (row.rem_euclid(self.rows()), col.rem_euclid(self.cols()))
+        } else if (0..self.rows()).contains(&row) && (0..self.cols()).contains(&col) {
+            (row, col)
+        } else {
+            return false;
+        };
+
+        self.tiles[row as usize][col as usize] != b'#'
+    }
+}
+
+// Breadth-first search from the start that only expands as far as it has been asked about
+#[derive(Debug)]
+struct Reachability<'g, 'a> {
+    garden: &'g Garden<'a>,
+    tiled: bool,
+    visited: HashSet<Position>,
+    frontier: Vec<Position>,
+    // Number of plots whose shortest distance from the start is the index
+    at_distance: Vec<u64>,
+}
+
+impl<'g, 'a> Reachability<'g, 'a> {
+    fn new(garden: &'g Garden<'a>, tiled: bool) -> Self {
+        Self {
+            garden,
+            tiled,
+            visited: HashSet::from([garden.start]),
+            frontier: vec![garden.start],
+            at_distance: vec![1],
+        }
+    }
+
+    fn expand(&mut self) {
+        let mut next = Vec::new();
+
+        for &(row, col) in &self.frontier {
+            for neighbour in [(row - 1, col), (row + 1, col), (row, col - 1), (row, col + 1)] {
+                if self.garden.is_plot(neighbour, self.tiled) && self.visited.insert(neighbour) {
+                    next.push(neighbour);
+                }
+            }
+        }
+
+        self.at_distance.push(next.len() as u64);
+        self.frontier = next;
+    }
+
+    // Plots the elf can stand on after exactly `steps` steps. Stepping back and forth means every plot
+    // at a shorter distance with the same parity can be reached too.
+    fn count(&mut self, steps: i64) -> u64 {
+        while (self.at_distance.len() as i64) <= steps && !self.frontier.is_empty() {
+            self.expand();
+        }
+
+        self.at_distance.iter().enumerate()
+            .take(steps as usize + 1)
+            .filter(|&(distance, _)| distance as i64 % 2 == steps % 2)
+            .map(|(_, &plots)| plots)
+            .sum()
+    }
+}
+
+const NO_START: &str = "Expected the map to contain a starting position S";
+
+fn solve_part_1(input: &str, steps: i64) -> Result<u64, String> {
+    let garden = Garden::parse(input).ok_or(NO_START)?;
+
+    Ok(Reachability::new(&garden, false).count(steps))
+}
+
+// Once the search has grown past a few copies of the map, each additional copy adds the same growth to
+// the reachable count as the one before, so the counts at steps % size + k * size follow a quadratic in k.
+// Finds the first four consecutive k whose counts have a zero third difference and extends the quadratic
+// from there.
+fn solve_part_2(input: &str, steps: i64) -> Result<u64, String> {
+    let garden = Garden::parse(input).ok_or(NO_START)?;
+
+    if garden.rows() != garden.cols() {
+        return Err(format!("Expected a square map but found {}x{}", garden.rows(), garden.cols()));
+    }
+
+    let size = garden.rows();
+    let remainder = steps % size;
+    let mut reachability = Reachability::new(&garden, true);
+
+    for offset in 0..=MAX_SETTLING_REPETITIONS {
+        // Close enough to count directly
+        if remainder + (offset + 3) * size >= steps {
+            return Ok(reachability.count(steps));
+        }
+
+        let counts: Vec<i128> = (0..4).map(|k| reachability.count(remainder + (offset + k) * size) as i128).collect();
+
+        if counts[3] - 3 * counts[2] + 3 * counts[1] - counts[0] != 0 {
+            continue;
+        }
+
+        let first_difference = counts[1] - counts[0];
+        let second_difference = counts[2] - 2 * counts[1] + counts[0];
+        let n = ((steps - remainder) / size - offset) as i128;
+
+        return Ok((counts[0] + n * first_difference + n * (n - 1) / 2 * second_difference) as u64);
+    }
+
+    Err(format!("Reachable plots did not settle into a quadratic within {} repetitions of the map", MAX_SETTLING_REPETITIONS))
+}
+
+pub fn answers() {
+    let (input, steps) = (include_str!("input.txt"), (64, 26_501_365));
+    // let (input, steps) = (include_str!("test.txt"), (6, 5000));
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input, steps.0));
+    print_answer("P2", solve_part_2(input, steps.1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim(), 6), Ok(16));
+    }
+
+    #[test]
+    fn part_2_example() {
+        let input = include_str!("test.txt").trim();
+
+        assert_eq!(solve_part_2(input, 6), Ok(16));
+        assert_eq!(solve_part_2(input, 10), Ok(50));
+        assert_eq!(solve_part_2(input, 50), Ok(1594));
+        assert_eq!(solve_part_2(input, 100), Ok(6536));
+        assert_eq!(solve_part_2(input, 500), Ok(167004));
+        assert_eq!(solve_part_2(input, 1000), Ok(668697));
+        assert_eq!(solve_part_2(input, 5000), Ok(16733044));
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/22
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_digit() || ",~".contains(c),
+};
+
+type Coordinate = (u32, u32, u32);
+
+#[derive(Debug)]
+struct Brick {
+    // Opposite corners with start <= end on every axis
+    start: Coordinate,
+    end: Coordinate,
+}
+
+impl Brick {
+    fn parse(line: &str) -> Option<Self> {
+        let parse_coordinate = |coordinate: &str| -> Option<Coordinate> {
+            let mut values = coordinate.split(',').map(|v| v.parse::<u32>().ok());
+            let coordinate = (values.next()??, values.next()??, values.next()??);
+            values.next().is_none().then_some(coordinate)
+        };
+
+        let (a, b) = line.split_once('~')?;
+        let (a, b) = (parse_coordinate(a)?, parse_coordinate(b)?);
+
+        Some(Self {
+            start: (a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+            end: (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)),
+        })
+    }
+
+    fn footprint(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (self.start.0..=self.end.0).flat_map(move |x| (self.start.1..=self.end.1).map(move |y| (x, y)))
+    }
+
+    fn height(&self) -> u32 {
+        self.end.2 - self.start.2 + 1
+    }
+}
+
+// Which bricks rest directly on which once everything has fallen, indexed by brick
+#[derive(Debug)]
+struct Support {
+    below: Vec<HashSet<usize>>,
+    above: Vec<HashSet<usize>>,
+}
+
+// Drops the bricks from lowest to highest, tracking the top brick over every column
+fn settle(mut bricks: Vec<Brick>) -> Support {
+    bricks.sort_unstable_by_key(|brick| brick.start.2);
+
+    let mut top = HashMap::<(u32, u32), (u32, usize)>::new();
+    let mut below = vec![HashSet::new(); bricks.len()];
+    let mut above = vec![HashSet::new(); bricks.len()];
+
+    for (i, brick) in bricks.iter().enumerate() {
+        let landing = brick.footprint().filter_map(|column| top.get(&column)).map(|&(z, _)| z).max().unwrap_or(0);
+
+        for column in brick.footprint() {
+            if let Some(&(z, j)) = top.get(&column) {
+                if z == landing {
+                    below[i].insert(j);
+                    above[j].insert(i);
+                }
+            }
+
+            top.insert(column, (landing + brick.height(), i));
+        }
+    }
+
+    Support {
+        below,
+        above,
+    }
+}
+
+impl Support {
+    // Bricks that would fall if `removed` were disintegrated, not counting itself
+    fn chain_reaction(&self, removed: usize) -> usize {
+        let mut fallen = HashSet::from([removed]);
+        let mut queue = VecDeque::from([removed]);
+
+        while let Some(brick) = queue.pop_front() {
+            for &resting in &self.above[brick] {
+                if !fallen.contains(&resting) && self.below[resting].iter().all(|support| fallen.contains(support)) {
+                    fallen.insert(resting);
+                    queue.push_back(resting);
+                }
+            }
+        }
+
+        fallen.len() - 1
+    }
+}
+
+fn parse_bricks(input: &str) -> Result<Vec<Brick>, String> {
+    input.lines()
+        .map(|line| Brick::parse(line).ok_or_else(|| format!("Expected a brick like 1,0,1~1,2,1 but found: {}", line)))
+        .collect()
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    let support = settle(parse_bricks(input)?);
+
+    // Safe when every brick resting on it has another brick to rest on
+    Ok(support.above.iter()
+        .filter(|resting| resting.iter().all(|&brick| support.below[brick].len() > 1))
+        .count() as u64)
+}
+
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    let support = settle(parse_bricks(input)?);
+
+    Ok((0..support.below.len()).map(|brick| support.chain_reaction(brick) as u64).sum())
+}
+
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(5));
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), Ok(7));
+    }
+}
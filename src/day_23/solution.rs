@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/23
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| "#.^>v<".contains(c),
+};
+
+type Position = (usize, usize);
+
+// Junctions are tracked as bits of a u64 while searching for the longest hike
+const MAX_JUNCTIONS: usize = 64;
+
+#[derive(Debug, Copy, Clone)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    fn slope(&self) -> u8 {
+        match self {
+            Self::Up => b'^',
+            Self::Down => b'v',
+            Self::Left => b'<',
+            Self::Right => b'>',
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Trails<'a> {
+    tiles: Vec<&'a [u8]>,
+    start: Position,
+    end: Position,
+}
+
+impl<'a> Trails<'a> {
+    fn parse(input: &'a str) -> Option<Self> {
+        let tiles: Vec<&[u8]> = input.lines().map(|line| line.as_bytes()).collect();
+
+        let start = (0, tiles.first()?.iter().position(|&tile| tile == b'.')?);
+        let end = (tiles.len() - 1, tiles.last()?.iter().position(|&tile| tile == b'.')?);
+
+        Some(Self {
+            tiles,
+            start,
+            end,
+        })
+    }
+
+    fn step(&self, (row, col): Position, direction: Direction) -> Option<Position> {
+        let next = match direction {
+            Direction::Up => (row.checked_sub(1)?, col),
+            Direction::Down => (row + 1, col),
+            Direction::Left => (row, col.checked_sub(1)?),
+            Direction::Right => (row, col + 1),
+        };
+
+        match self.tiles.get(next.0)?.get(next.1)? {
+            b'#' => None,
+            _ => Some(next),
+        }
+    }
+
+    // Positions reachable in one step, where slippery slopes only allow stepping downhill
+    fn neighbours(&self, position: Position, slippery: bool) -> impl Iterator<Item = Position> + '_ {
+        let tile = self.tiles[position.0][position.1];
+
+        Direction::ALL.into_iter()
+            .filter(move |direction| !slippery || tile == b'.' || tile == direction.slope())
+            .filter_map(move |direction| self.step(position, direction))
+    }
+
+    // The start, the end, and every tile where paths meet
+    fn junctions(&self) -> Vec<Position> {
+        let mut junctions = vec![self.start, self.end];
+
+        for (row, line) in self.tiles.iter().enumerate() {
+            for (col, &tile) in line.iter().enumerate() {
+                if tile != b'#' && self.neighbours((row, col), false).count() > 2 {
+                    junctions.push((row, col));
+                }
+            }
+        }
+
+        junctions
+    }
+
+    // Collapses every corridor between two junctions into a single edge weighted by its length
+    fn junction_graph(&self, junctions: &[Position], slippery: bool) -> Vec<Vec<(usize, u32)>> {
+        let ids: HashMap<Position, usize> = junctions.iter().enumerate().map(|(id, &position)| (position, id)).collect();
+
+        junctions.iter().map(|&junction| {
+            self.neighbours(junction, slippery).filter_map(|first| {
+                let (mut previous, mut current, mut length) = (junction, first, 1);
+
+                while !ids.contains_key(&current) {
+                    let next = self.neighbours(current, slippery).find(|&next| next != previous)?;
+                    (previous, current, length) = (current, next, length + 1);
+                }
+
+                Some((ids[&current], length))
+            }).collect()
+        }).collect()
+    }
+}
+
+// Length of the longest path from `from` to `to` that visits no junction twice
+fn longest_hike(graph: &[Vec<(usize, u32)>], from: usize, to: usize, visited: u64) -> Option<u32> {
+    if from == to {
+        return Some(0);
+    }
+
+    graph[from].iter()
+        .filter(|&&(next, _)| visited & (1 << next) == 0)
+        .filter_map(|&(next, length)| longest_hike(graph, next, to, visited | (1 << next)).map(|rest| rest + length))
+        .max()
+}
+
+fn solve(input: &str, slippery: bool) -> Result<u64, String> {
+    let trails = Trails::parse(input).ok_or("Expected a path through the top and bottom rows")?;
+
+    let junctions = trails.junctions();
+    if junctions.len() > MAX_JUNCTIONS {
+        return Err(format!("Found {} junctions but at most {} are supported", junctions.len(), MAX_JUNCTIONS));
+    }
+
+    // The start and end are the first two junctions
+    let graph = trails.junction_graph(&junctions, slippery);
+
+    longest_hike(&graph, 0, 1, 1).map(|length| length as u64).ok_or_else(|| String::from("No hike reaches the end"))
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    solve(input, true)
+}
+
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    solve(input, false)
+}
+
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(94));
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), Ok(154));
+    }
+}
@@ -0,0 +1,190 @@
+use num::{BigInt, BigRational, ToPrimitive, Zero};
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/24
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_digit() || " ,@-".contains(c),
+};
+
+type Vector = [i128; 3];
+
+#[derive(Debug)]
+struct Hailstone {
+    position: Vector,
+    velocity: Vector,
+}
+
+impl Hailstone {
+    fn parse(line: &str) -> Option<Self> {
+        let parse_vector = |vector: &str| -> Option<Vector> {
+            let values = vector.split(',').map(|v| v.trim().parse::<i128>().ok()).collect::<Option<Vec<i128>>>()?;
+            values.try_into().ok()
+        };
+
+        let (position, velocity) = line.split_once('@')?;
+
+        Some(Self {
+            position: parse_vector(position)?,
+            velocity: parse_vector(velocity)?,
+        })
+    }
+
+    // Whether the paths of two hailstones cross within the test area on the X and Y axes at times that
+    // are in the future for both, ignoring Z. Everything stays in integers by scaling with the
+    // determinant instead of dividing by it.
+    fn crosses_within(&self, other: &Hailstone, (low, high): (i128, i128)) -> bool {
+        let [vx, vy, _] = self.velocity;
+        let [wx, wy, _] = other.velocity;
+
+        let mut determinant = vx * wy - vy * wx;
+        if determinant == 0 {
+            return false;
+        }
+
+        let dx = other.position[0] - self.position[0];
+        let dy = other.position[1] - self.position[1];
+
+        // The paths meet at times t = t_scaled / determinant for self and s = s_scaled / determinant for other
+        let mut t_scaled = dx * wy - dy * wx;
+        let mut s_scaled = dx * vy - dy * vx;
+
+        if determinant < 0 {
+            (determinant, t_scaled, s_scaled) = (-determinant, -t_scaled, -s_scaled);
+        }
+
+        if t_scaled < 0 || s_scaled < 0 {
+            return false;
+        }
+
+        let x_scaled = self.position[0] * determinant + t_scaled * vx;
+        let y_scaled = self.position[1] * determinant + t_scaled * vy;
+        let area = low * determinant..=high * determinant;
+
+        area.contains(&x_scaled) && area.contains(&y_scaled)
+    }
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn subtract(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+// Three linear equations in the rock's position P and velocity V from a pair of hailstones.
+// The rock hits a hailstone when (P - p) x (V - v) = 0, which expands to
+// P x V - P x v - p x V + p x v = 0. Subtracting the expansions for hailstones i and j cancels P x V:
+// P x (vi - vj) + (pi - pj) x V = pi x vi - pj x vj
+fn pair_equations(i: &Hailstone, j: &Hailstone) -> [[i128; 7]; 3] {
+    let [dvx, dvy, dvz] = subtract(i.velocity, j.velocity);
+    let [dpx, dpy, dpz] = subtract(i.position, j.position);
+    let [cx, cy, cz] = subtract(cross(i.position, i.velocity), cross(j.position, j.velocity));
+
+    // Coefficients of Px, Py, Pz, Vx, Vy, Vz followed by the constant
+    [
+        [0, dvz, -dvy, 0, -dpz, dpy, cx],
+        [-dvz, 0, dvx, dpz, 0, -dpx, cy],
+        [dvy, -dvx, 0, -dpy, dpx, 0, cz],
+    ]
+}
+
+// Gauss-Jordan elimination on an augmented matrix, returning None when it is singular
+fn solve_linear(rows: &[[i128; 7]]) -> Option<Vec<BigRational>> {
+    let mut matrix: Vec<Vec<BigRational>> = rows.iter()
+        .map(|row| row.iter().map(|&value| BigRational::from_integer(BigInt::from(value))).collect())
+        .collect();
+    let size = matrix.len();
+
+    for col in 0..size {
+        let pivot = (col..size).find(|&row| !matrix[row][col].is_zero())?;
+        matrix.swap(col, pivot);
+
+        let divisor = matrix[col][col].clone();
+        for value in matrix[col].iter_mut() {
+            *value /= &divisor;
+        }
+
+        let pivot_row = matrix[col].clone();
+        for (row, values) in matrix.iter_mut().enumerate() {
+            if row != col && !values[col].is_zero() {
+                let factor = values[col].clone();
+                for (value, pivot) in values.iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= &factor * pivot;
+                }
+            }
+        }
+    }
+
+    Some(matrix.into_iter().map(|row| row[size].clone()).collect())
+}
+
+// Position and velocity of a rock that hits every hailstone, found from the first three hailstones
+// whose equations pin it down
+fn find_rock(hailstones: &[Hailstone]) -> Option<Vec<BigRational>> {
+    let n = hailstones.len();
+
+    (0..n).flat_map(|i| (i + 1..n).flat_map(move |j| (j + 1..n).map(move |k| (i, j, k)))).find_map(|(i, j, k)| {
+        let mut rows = pair_equations(&hailstones[i], &hailstones[j]).to_vec();
+        rows.extend(pair_equations(&hailstones[i], &hailstones[k]));
+
+        solve_linear(&rows)
+    })
+}
+
+fn parse_hailstones(input: &str) -> Result<Vec<Hailstone>, String> {
+    input.lines()
+        .map(|line| Hailstone::parse(line).ok_or_else(|| format!("Expected a hailstone like 19, 13, 30 @ -2, 1, -2 but found: {}", line)))
+        .collect()
+}
+
+fn solve_part_1(input: &str, test_area: (i128, i128)) -> Result<u64, String> {
+    let hailstones = parse_hailstones(input)?;
+
+    Ok(hailstones.iter().enumerate().map(|(i, a)| {
+        hailstones[i + 1..].iter().filter(|b| a.crosses_within(b, test_area)).count() as u64
+    }).sum())
+}
+
+fn solve_part_2(input: &str) -> Result<i128, String> {
+    let hailstones = parse_hailstones(input)?;
+
+    let rock = find_rock(&hailstones).ok_or("No three hailstones determine the rock's path")?;
+
+    if !rock[..3].iter().all(|coordinate| coordinate.is_integer()) {
+        return Err(String::from("The rock would have to start at a fractional position"));
+    }
+
+    let total: BigRational = rock[..3].iter().sum();
+    total.to_integer().to_i128().ok_or_else(|| format!("The sum of the rock's coordinates does not fit in an i128: {}", total))
+}
+
+pub fn answers() {
+    let (input, test_area) = (include_str!("input.txt"), (200_000_000_000_000, 400_000_000_000_000));
+    // let (input, test_area) = (include_str!("test.txt"), (7, 27));
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input, test_area));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim(), (7, 27)), Ok(2));
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), Ok(47));
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/25
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_lowercase() || " :".contains(c),
+};
+
+// Wires that have to be disconnected to split the components into two groups
+const WIRES_TO_CUT: u32 = 3;
+
+type ComponentId = usize;
+
+// Undirected graph stored as pairs of arcs with unit capacity, where arc i ^ 1 runs the opposite way
+// to arc i. Pushing flow along one arc frees up capacity on its partner.
+#[derive(Debug)]
+struct Wiring {
+    heads: Vec<ComponentId>,
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl Wiring {
+    fn parse(input: &str) -> Option<Self> {
+        let mut ids = HashMap::<&str, ComponentId>::new();
+        let mut wiring = Self { heads: Vec::new(), adjacent: Vec::new() };
+
+        let mut intern = |name, wiring: &mut Self| *ids.entry(name).or_insert_with(|| {
+            wiring.adjacent.push(Vec::new());
+            wiring.adjacent.len() - 1
+        });
+
+        for line in input.lines() {
+            let (component, connected) = line.split_once(": ")?;
+            let from = intern(component, &mut wiring);
+
+            for other in connected.split_whitespace() {
+                let to = intern(other, &mut wiring);
+                wiring.connect(from, to);
+            }
+        }
+
+        Some(wiring)
+    }
+
+    fn connect(&mut self, a: ComponentId, b: ComponentId) {
+        self.adjacent[a].push(self.heads.len());
+        self.heads.push(b);
+        self.adjacent[b].push(self.heads.len());
+        self.heads.push(a);
+    }
+
+    fn len(&self) -> usize {
+        self.adjacent.len()
+    }
+
+    // Searches for a path with spare capacity from source to sink. Returns the arc used to arrive at
+    // each component when the sink is reached, and otherwise which components could be reached.
+    fn augmenting_path(&self, capacity: &[u32], source: ComponentId, sink: ComponentId) -> Result<Vec<Option<usize>>, Vec<bool>> {
+        let mut arrived_by = vec![None; self.len()];
+        let mut reached = vec![false; self.len()];
+        let mut queue = VecDeque::from([source]);
+        reached[source] = true;
+
+        while let Some(component) = queue.pop_front() {
+            if component == sink {
+                return Ok(arrived_by);
+            }
+
+            for &arc in &self.adjacent[component] {
+                let next = self.heads[arc];
+                if capacity[arc] > 0 && !reached[next] {
+                    reached[next] = true;
+                    arrived_by[next] = Some(arc);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        Err(reached)
+    }
+
+    // Components on the source's side of a cut of at most `limit` wires between source and sink,
+    // or None when more than `limit` wires would need cutting (Edmonds-Karp)
+    fn cut(&self, source: ComponentId, sink: ComponentId, limit: u32) -> Option<usize> {
+        let mut capacity = vec![1; self.heads.len()];
+
+        for _ in 0..=limit {
+            let arrived_by = match self.augmenting_path(&capacity, source, sink) {
+                Ok(arrived_by) => arrived_by,
+                Err(reached) => return Some(reached.iter().filter(|&&r| r).count()),
+            };
+
+            let mut component = sink;
+            while let Some(arc) = arrived_by[component] {
+                capacity[arc] -= 1;
+                capacity[arc ^ 1] += 1;
+                component = self.heads[arc ^ 1];
+            }
+        }
+
+        None
+    }
+}
+
+// Exactly three wires separate the two groups, so every component in the other group is split from
+// the first one by a cut of three wires, while components in the same group need at least four.
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    let wiring = Wiring::parse(input).ok_or("Expected lines like \"jqt: rhn xhk nvd\"")?;
+
+    for sink in 1..wiring.len() {
+        if let Some(group) = wiring.cut(0, sink, WIRES_TO_CUT) {
+            return Ok((group * (wiring.len() - group)) as u64);
+        }
+    }
+
+    Err(format!("No {} wires split the components into two groups", WIRES_TO_CUT))
+}
+
+// Day 25 has no second puzzle, the last star is awarded for collecting all the others
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(54));
+    }
+}
@@ -1,154 +1,174 @@
-use std::{collections::{HashSet, HashMap}, iter::{self, Peekable, Enumerate}, str::Chars};
+use std::collections::HashMap;
+
+use crate::utils::input::{check_input, InputSpec};
 
 // https://adventofcode.com/2023/day/3
 
-fn solve_part_1(input: &str) -> u64 {
-    fn find_symbol_locations(schematic: &str) -> HashSet<(usize, usize)> {
-        let mut locations = HashSet::<(usize, usize)>::new();
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: true,
+    allowed: |c| c.is_ascii_graphic(),
+};
+
+type Position = (usize, usize);
+
+#[derive(Debug)]
+struct Number {
+    value: u64,
+    row: usize,
+    // First and last columns of the digits
+    start: usize,
+    end: usize,
+}
 
-        for (row, line) in schematic.lines().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                if !ch.is_ascii_digit() && ch != '.' {
-                    locations.insert((row, col));
-                }
-            }
-        }
+#[derive(Debug, Copy, Clone)]
+enum Reduction {
+    Product,
+    Sum,
+    Max,
+}
 
-        locations
+impl Reduction {
+    fn reduce(&self, values: impl Iterator<Item = u64>) -> u64 {
+        match self {
+            Self::Product => values.product(),
+            Self::Sum => values.sum(),
+            Self::Max => values.max().unwrap_or(0),
+        }
     }
+}
 
-    fn is_symbol_adjacent(row: usize, col_span: &(usize, usize), symbol_locations: &HashSet<(usize, usize)>) -> bool {
-        let row_min: usize = if row == 0 { 0 } else { row - 1 };
-        let col_min: usize = if col_span.0 == 0 { 0 } else { col_span.0 - 1 };
-        let row_above = (col_min..=col_span.1 + 1).map(|col| (row_min, col));
-        let row_below = (col_min..=col_span.1 + 1).map(|col| (row + 1, col));
-        let row_beginning = (row, col_min);
-        let row_end = (row, col_span.1 + 1);
-
-        let mut adjacent_locations = iter::once(row_beginning)
-            .chain(iter::once(row_end))
-            .chain(row_above)
-            .chain(row_below);
-
-        adjacent_locations.any(|location| symbol_locations.contains(&location))
-    }
+// Which symbols count as gears, how many part numbers they must touch, and how those numbers
+// combine into the gear's ratio
+#[derive(Debug)]
+struct GearRule {
+    symbols: &'static str,
+    parts: usize,
+    reduction: Reduction,
+}
 
-    fn parse_number(chars: &mut Peekable<Enumerate<Chars>>) -> (u64, usize) {
-        let mut num: u64 = 0;
-        let mut end: usize = usize::MAX;
+static STANDARD_GEARS: GearRule = GearRule {
+    symbols: "*",
+    parts: 2,
+    reduction: Reduction::Product,
+};
+
+#[derive(Debug)]
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: HashMap<Position, char>,
+    // Index into `numbers` for every position covered by a digit
+    number_positions: HashMap<Position, usize>,
+}
 
-        while let Some(&(col, ch)) = chars.peek() {
-            if !ch.is_ascii_digit() {
-                break;
-            }
+impl Schematic {
+    fn parse(schematic: &str) -> Self {
+        let mut numbers = Vec::<Number>::new();
+        let mut symbols = HashMap::<Position, char>::new();
+        let mut number_positions = HashMap::<Position, usize>::new();
 
-            num = (num * 10) + (ch.to_digit(10).unwrap()) as u64;
-            end = col;
+        for (row, line) in schematic.lines().enumerate() {
+            let mut current: Option<Number> = None;
 
-            chars.next();
-        }
+            for (col, ch) in line.chars().enumerate() {
+                if let Some(digit) = ch.to_digit(10) {
+                    let number = current.get_or_insert(Number { value: 0, row, start: col, end: col });
+                    number.value = (number.value * 10) + digit as u64;
+                    number.end = col;
+                    number_positions.insert((row, col), numbers.len());
+                    continue;
+                }
 
-        (num, end)
-    }
+                if let Some(number) = current.take() {
+                    numbers.push(number);
+                }
 
-    fn process_schematic_line(row: usize, line: &str, symbol_locations: &HashSet<(usize, usize)>) -> u64 {
-        let mut line_sum: u64 = 0;
-        
-        let mut chars = line.chars().enumerate().peekable();
-        while let Some(&(start, ch)) = chars.peek() {
-            if ch.is_ascii_digit() {
-                let (num, end) = parse_number(&mut chars);
-                if is_symbol_adjacent(row, &(start, end), symbol_locations) {
-                    line_sum += num;
+                if ch != '.' {
+                    symbols.insert((row, col), ch);
                 }
-            } else {
-                chars.next();
             }
-        }
-
-        line_sum
-    }
-
-    let symbol_locations = find_symbol_locations(input);
-    input.lines().enumerate().map(|(row, line)| process_schematic_line(row, line, &symbol_locations)).sum()
-}
-
-fn solve_part_2(input: &str) -> u64 {
-    fn find_gear_locations(schematic: &str) -> HashSet<(usize, usize)> {
-        let mut locations = HashSet::<(usize, usize)>::new();
 
-        for (row, line) in schematic.lines().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                if ch == '*' {
-                    locations.insert((row, col)); 
-                }
+            if let Some(number) = current.take() {
+                numbers.push(number);
             }
         }
 
-        locations
+        Self {
+            numbers,
+            symbols,
+            number_positions,
+        }
     }
 
-    fn find_adjacent_gears<'a>(row: usize, col_span: &'a(usize, usize), gear_locations: &HashSet<(usize, usize)>) -> Vec<(usize, usize)> {
-        let row_min: usize = if row == 0 { 0 } else { row - 1 };
-        let col_min: usize = if col_span.0 == 0 { 0 } else { col_span.0 - 1 };
-        let row_above = (col_min..=col_span.1 + 1).map(|col| (row_min, col));
-        let row_below = (col_min..=col_span.1 + 1).map(|col| (row + 1, col));
-        let row_beginning = (row, col_min);
-        let row_end = (row, col_span.1 + 1);
+    // Positions touching the columns start..=end of a row, including diagonally
+    fn surrounding(row: usize, start: usize, end: usize) -> impl Iterator<Item = Position> {
+        let row_min = row.saturating_sub(1);
+        let col_min = start.saturating_sub(1);
 
-        let adjacent_locations = iter::once(row_beginning)
-            .chain(iter::once(row_end))
-            .chain(row_above)
-            .chain(row_below);
-
-        adjacent_locations.filter(|location| gear_locations.contains(&location)).collect()
+        (row_min..=row + 1)
+            .flat_map(move |r| (col_min..=end + 1).map(move |c| (r, c)))
+            .filter(move |&(r, c)| r != row || c < start || c > end)
     }
 
-    fn parse_number(chars: &mut Peekable<Enumerate<Chars>>) -> (u64, usize) {
-        let mut num: u64 = 0;
-        let mut end: usize = usize::MAX;
+    fn symbols_adjacent_to(&self, number: &Number) -> Vec<(Position, char)> {
+        Self::surrounding(number.row, number.start, number.end)
+            .filter_map(|position| self.symbols.get(&position).map(|&symbol| (position, symbol)))
+            .collect()
+    }
 
-        while let Some(&(col, ch)) = chars.peek() {
-            if !ch.is_ascii_digit() {
-                break;
-            }
+    fn numbers_adjacent_to(&self, position: Position) -> Vec<&Number> {
+        let mut indices: Vec<usize> = Self::surrounding(position.0, position.1, position.1)
+            .filter_map(|p| self.number_positions.get(&p).copied())
+            .collect();
 
-            num = (num * 10) + (ch.to_digit(10).unwrap()) as u64;
-            end = col;
+        indices.sort_unstable();
+        indices.dedup();
 
-            chars.next();
-        }
-
-        (num, end)
+        indices.into_iter().map(|i| &self.numbers[i]).collect()
     }
 
-    fn process_schematic_line(row: usize, line: &str, gear_locations: &HashSet<(usize, usize)>, gear_adjacent_parts: &mut HashMap<(usize, usize), Vec<u64>>) {
-        let mut chars = line.chars().enumerate().peekable();
-        while let Some(&(start, ch)) = chars.peek() {
-            if ch.is_ascii_digit() {
-                let (num, end) = parse_number(&mut chars);
-                for gear_location in find_adjacent_gears(row, &(start, end), gear_locations) {
-                    gear_adjacent_parts.entry(gear_location).and_modify(|v| v.push(num)).or_insert(vec![num]);
-                }
-            } else {
-                chars.next();
-            }
-        }
+    fn gear_ratios<'a>(&'a self, rule: &'a GearRule) -> impl Iterator<Item = u64> + 'a {
+        self.symbols.iter()
+            .filter(|(_, &symbol)| rule.symbols.contains(symbol))
+            .map(|(&position, _)| self.numbers_adjacent_to(position))
+            .filter(|adjacent_parts| adjacent_parts.len() == rule.parts)
+            .map(|adjacent_parts| rule.reduction.reduce(adjacent_parts.iter().map(|number| number.value)))
     }
 
-    let gear_locations = find_gear_locations(input);
-    let mut gear_adjacent_parts = HashMap::<(usize, usize), Vec::<u64>>::new();
+    // Every symbol with the part numbers around it, from top to bottom and left to right
+    fn symbol_report(&self) -> Vec<(Position, char, Vec<u64>)> {
+        let mut report: Vec<(Position, char, Vec<u64>)> = self.symbols.iter().map(|(&position, &symbol)| {
+            (position, symbol, self.numbers_adjacent_to(position).iter().map(|number| number.value).collect())
+        }).collect();
 
-    for (row, line) in input.lines().enumerate() {
-        process_schematic_line(row, line, &gear_locations, &mut gear_adjacent_parts);
+        report.sort_unstable_by_key(|&(position, _, _)| position);
+        report
     }
+}
+
+fn solve_part_1(input: &str) -> u64 {
+    let schematic = Schematic::parse(input);
 
-    gear_adjacent_parts.values().filter(|adjacent_parts| adjacent_parts.len() == 2).fold(0, |sum, adjacent_parts| sum + (adjacent_parts[0] * adjacent_parts[1]))
+    schematic.numbers.iter().filter(|number| !schematic.symbols_adjacent_to(number).is_empty()).map(|number| number.value).sum()
+}
+
+fn solve_part_2(input: &str) -> u64 {
+    Schematic::parse(input).gear_ratios(&STANDARD_GEARS).sum()
 }
 
 pub fn answers() {
-    let input = include_str!("input.txt").trim();
+    let input = include_str!("input.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
 
     println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
     println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
 }
+
+pub fn print_symbol_report() {
+    let input = include_str!("input.txt").trim();
+
+    for ((row, col), symbol, parts) in Schematic::parse(input).symbol_report() {
+        println!("{} at ({}, {}): {} part(s) {:?}", symbol, row, col, parts.len(), parts);
+    }
+}
@@ -1,57 +1,111 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use regex::Regex;
-use once_cell::sync::Lazy;
+use crate::utils::lines::sum_lines;
+use crate::utils::parse::{ParseResult, Parser};
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
 
 // https://adventofcode.com/2023/day/4
 
-static CARD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Card\s+(?<id>\d+):\s+(?<winning_numbers>[0-9\s]+)\s\|\s+(?<card_numbers>[0-9\s]+)$").unwrap());
-
-fn solve_part_1(input: &str) -> u64 {
-    fn check_card(card: &str) -> u64 {
-        if let Some(captures) = CARD_RE.captures(card) {
-            let winning_numbers: &HashSet<&str> = &captures["winning_numbers"].split(' ').filter(|&s| s != "").collect();
-            let card_numbers: &HashSet<&str> = &captures["card_numbers"].split(' ').filter(|&s| s != "").collect();
-
-            match winning_numbers.intersection(card_numbers).count() {
-                0 => 0,
-                num_matches => 1 << (num_matches - 1)
-            }
-        } else {
-            eprintln!("[P1 :: ERROR] Expected to find regex captures but found none for: {}", card);
-            0
-        }
-    }
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: false,
+    allowed: |c| c.is_ascii_alphanumeric() || " :|".contains(c),
+};
 
-    input.lines().map(check_card).sum()
+#[derive(Debug)]
+struct Scratchcard {
+    id: usize,
+    winning: HashSet<u32>,
+    held: HashSet<u32>,
 }
 
-fn solve_part_2(input: &str) -> u64 {
-    fn check_card(card: &str, checked_cards: &mut HashMap<u8, u64>) -> u64 {
-        if let Some(captures) = CARD_RE.captures(card) {
-            let winning_numbers: &HashSet<&str> = &captures["winning_numbers"].split(' ').filter(|&s| s != "").collect();
-            let card_numbers: &HashSet<&str> = &captures["card_numbers"].split(' ').filter(|&s| s != "").collect();
+impl Scratchcard {
+    // Card <id>: <winning numbers> | <numbers held>
+    fn parse(card: &str) -> ParseResult<Self> {
+        let mut parser = Parser::new(card);
+
+        parser.tag("Card")?;
+        parser.whitespace()?;
+        let id = parser.unsigned()?;
+        parser.tag(":")?;
+        parser.whitespace()?;
+        let winning = parser.whitespace_separated(Parser::unsigned)?;
+        parser.whitespace()?;
+        parser.tag("|")?;
+        parser.whitespace()?;
+        let held = parser.whitespace_separated(Parser::unsigned)?;
+        parser.end()?;
+
+        Ok(Self {
+            id,
+            winning: winning.into_iter().collect(),
+            held: held.into_iter().collect(),
+        })
+    }
+
+    fn matches(&self) -> usize {
+        self.held.intersection(&self.winning).count()
+    }
+
+    fn points(&self) -> u64 {
+        match self.matches() {
+            0 => 0,
+            num_matches => 1 << (num_matches - 1)
+        }
+    }
+}
 
-            let num_matches: u8 = winning_numbers.intersection(card_numbers).count() as u8;
-            let card_id: u8 = *(&captures["id"].parse().unwrap());
+// Copies of each card held once every won copy has been handed out, in the same order as the cards.
+// A card with n matches wins one copy of each of the next n cards for every copy of it held.
+fn count_copies(cards: &[Scratchcard]) -> Vec<u64> {
+    let mut copies = vec![1; cards.len()];
 
-            let num_additional_cards: u64 = num_matches as u64 + (card_id + 1..=card_id + num_matches).map(|id| checked_cards.get(&id).or(Some(&0)).unwrap()).sum::<u64>();
-            checked_cards.insert(card_id, num_additional_cards);
+    for (i, card) in cards.iter().enumerate() {
+        let last = (i + card.matches()).min(cards.len() - 1);
 
-            1 + num_additional_cards
-        } else {
-            eprintln!("[P1 :: ERROR] Expected to find regex captures but found none for: {}", card);
-            0
+        for j in i + 1..=last {
+            copies[j] += copies[i];
         }
     }
 
-    let mut checked_cards: HashMap<u8, u64> = HashMap::new();
-    input.lines().rev().map(|card| check_card(card, &mut checked_cards)).sum()
+    copies
+}
+
+// Stops at the first card that does not parse, since copies are counted by position
+fn parse_cards(input: &str) -> Result<Vec<Scratchcard>, String> {
+    input.lines().map(|card| Scratchcard::parse(card).map_err(|e| format!("{} in card: {}", e, card))).collect()
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    sum_lines(input, |card| Scratchcard::parse(card).map(|parsed| parsed.points()).map_err(|e| format!("{} in card: {}", e, card)))
+}
+
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    Ok(count_copies(&parse_cards(input)?).iter().sum())
 }
 
 pub fn answers() {
+    let input = include_str!("input.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+pub fn print_copies() {
     let input = include_str!("input.txt").trim();
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    let cards = match parse_cards(input) {
+        Ok(cards) => cards,
+        Err(e) => {
+            eprintln!("[COPIES :: ERROR] {}", e);
+            return;
+        }
+    };
+
+    for (card, copies) in cards.iter().zip(count_copies(&cards)) {
+        println!("Card {}: {} matches, {} copies", card.id, card.matches(), copies);
+    }
 }
@@ -0,0 +1,139 @@
+use crate::utils::interval::{Interval, IntervalSet};
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+// https://adventofcode.com/2023/day/5
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 60,
+    grid: false,
+    allowed: |c| c.is_ascii_lowercase() || c.is_ascii_digit() || " -:".contains(c),
+};
+
+// Destination start, source start and length of one line of a map
+#[derive(Debug)]
+struct MapRange {
+    destination: u64,
+    source: u64,
+    length: u64,
+}
+
+impl MapRange {
+    fn sources(&self) -> Option<Interval<u64>> {
+        Interval::half_open(self.source, self.source + self.length)
+    }
+
+    fn translate(&self, value: u64) -> u64 {
+        value - self.source + self.destination
+    }
+}
+
+// One "x-to-y map:" block
+#[derive(Debug)]
+struct Map {
+    ranges: Vec<MapRange>,
+}
+
+impl Map {
+    fn parse(block: &str) -> Option<Self> {
+        let ranges = block.lines().skip(1).map(|line| {
+            let numbers: Vec<u64> = line.split_whitespace().map(|n| n.parse::<u64>().ok()).collect::<Option<_>>()?;
+
+            match numbers.as_slice() {
+                &[destination, source, length] => Some(MapRange { destination, source, length }),
+                _ => None,
+            }
+        }).collect::<Option<Vec<MapRange>>>()?;
+
+        Some(Self {
+            ranges,
+        })
+    }
+
+    fn translate(&self, value: u64) -> u64 {
+        self.ranges.iter()
+            .find(|range| range.sources().is_some_and(|sources| sources.contains(value)))
+            .map_or(value, |range| range.translate(value))
+    }
+
+    // Translates every value in the set, where values not covered by any range map to themselves
+    fn translate_set(&self, values: &IntervalSet<u64>) -> IntervalSet<u64> {
+        let sources: IntervalSet<u64> = self.ranges.iter().filter_map(|range| range.sources()).collect();
+        let mut translated = values.difference(&sources);
+
+        for range in &self.ranges {
+            let Some(sources) = range.sources() else { continue };
+
+            translated.extend(values.intersection(&IntervalSet::from(sources)).iter().filter_map(|overlap| {
+                Interval::closed(range.translate(overlap.start()), range.translate(overlap.end()))
+            }));
+        }
+
+        translated
+    }
+}
+
+fn parse_almanac(input: &str) -> Option<(Vec<u64>, Vec<Map>)> {
+    let mut blocks = input.split("\n\n");
+
+    let seeds = blocks.next()?
+        .strip_prefix("seeds:")?
+        .split_whitespace()
+        .map(|n| n.parse::<u64>().ok())
+        .collect::<Option<Vec<u64>>>()?;
+
+    let maps = blocks.map(Map::parse).collect::<Option<Vec<Map>>>()?;
+
+    Some((seeds, maps))
+}
+
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    let (seeds, maps) = parse_almanac(input).ok_or("Expected a list of seeds followed by maps")?;
+
+    seeds.iter()
+        .map(|&seed| maps.iter().fold(seed, |value, map| map.translate(value)))
+        .min()
+        .ok_or_else(|| String::from("Expected at least one seed"))
+}
+
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    let (seeds, maps) = parse_almanac(input).ok_or("Expected a list of seeds followed by maps")?;
+
+    if seeds.len() % 2 != 0 {
+        return Err(format!("Expected pairs of seed starts and lengths but found {} numbers", seeds.len()));
+    }
+
+    let seeds: IntervalSet<u64> = seeds.chunks(2).filter_map(|pair| Interval::half_open(pair[0], pair[0] + pair[1])).collect();
+
+    maps.iter()
+        .fold(seeds, |values, map| map.translate_set(&values))
+        .iter()
+        .next()
+        .map(|interval| interval.start())
+        .ok_or_else(|| String::from("Expected at least one seed"))
+}
+
+pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_part_1(include_str!("test.txt").trim()), Ok(35));
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_part_2(include_str!("test.txt").trim()), Ok(46));
+    }
+}
@@ -1,64 +1,100 @@
 // https://adventofcode.com/2023/day/7
 
-use std::{collections::{BinaryHeap, HashMap}, cmp::Ordering};
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
-enum CardLabel {
-    Joker,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    T,
-    J,
-    Q,
-    K,
-    A,
-}
-
-impl CardLabel {
-    fn from(c: char) -> Result<Self, ()> {
-        match c {
-            '2' => Ok(Self::Two),
-            '3' => Ok(Self::Three),
-            '4' => Ok(Self::Four),
-            '5' => Ok(Self::Five),
-            '6' => Ok(Self::Six),
-            '7' => Ok(Self::Seven),
-            '8' => Ok(Self::Eight),
-            '9' => Ok(Self::Nine),
-            'T' => Ok(Self::T),
-            'J' => Ok(Self::J),
-            'Q' => Ok(Self::Q),
-            'K' => Ok(Self::K),
-            'A' => Ok(Self::A),
-            _ => Err(()),
-        }
+use std::{collections::HashMap, fmt};
+
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == ' ',
+};
+
+// Card ordering, wildcards and hand size for a variant of Camel Cards. Ties between hands of the
+// same type are broken card by card using the position of each label in `labels`.
+#[derive(Debug)]
+struct Ruleset {
+    // Labels ordered from weakest to strongest
+    labels: &'static str,
+    // Labels that join whichever group of cards makes the strongest hand
+    wildcards: &'static str,
+    hand_size: usize,
+}
+
+static STANDARD_RULES: Ruleset = Ruleset {
+    labels: "23456789TJQKA",
+    wildcards: "",
+    hand_size: 5,
+};
+
+static JOKER_RULES: Ruleset = Ruleset {
+    labels: "J23456789TQKA",
+    wildcards: "J",
+    hand_size: 5,
+};
+
+impl Ruleset {
+    fn rank(&self, label: char) -> Option<u8> {
+        self.labels.chars().position(|l| l == label).map(|rank| rank as u8)
+    }
+
+    // Whether every hand's type and card ranks can be packed into a u32 sort key
+    fn fits_sort_key(&self) -> bool {
+        self.labels.chars().count() <= 1 << RANK_BITS && HAND_TYPE_BITS + self.hand_size * RANK_BITS <= u32::BITS as usize
     }
 
-    fn from_with_jokers(c: char) -> Result<Self, ()> {
-        match c {
-            'J' => Ok(Self::Joker),
-            '2' => Ok(Self::Two),
-            '3' => Ok(Self::Three),
-            '4' => Ok(Self::Four),
-            '5' => Ok(Self::Five),
-            '6' => Ok(Self::Six),
-            '7' => Ok(Self::Seven),
-            '8' => Ok(Self::Eight),
-            '9' => Ok(Self::Nine),
-            'T' => Ok(Self::T),
-            'Q' => Ok(Self::Q),
-            'K' => Ok(Self::K),
-            'A' => Ok(Self::A),
-            _ => Err(()),
+    fn is_wild(&self, label: char) -> bool {
+        self.wildcards.contains(label)
+    }
+
+    fn parse_hand(&self, hand: &str) -> Result<Vec<u8>, HandError> {
+        let found = hand.chars().count();
+        if found != self.hand_size {
+            return Err(HandError::WrongLength { hand: hand.to_owned(), expected: self.hand_size, found });
         }
+
+        hand.chars().enumerate().map(|(position, label)| {
+            self.rank(label).ok_or_else(|| HandError::InvalidLabel { hand: hand.to_owned(), label, position })
+        }).collect()
     }
 
+    fn classify(&self, hand: &str) -> Classification {
+        let mut groups = HashMap::<char, u8>::new();
+        let mut num_wild = 0;
+
+        for card in hand.chars() {
+            if self.is_wild(card) {
+                num_wild += 1;
+            } else {
+                groups.entry(card).and_modify(|n| { *n += 1 }).or_insert(1);
+            }
+        }
+
+        // Largest groups first, with the stronger label first among groups of the same size
+        let mut groups: Vec<(char, u8)> = groups.into_iter().collect();
+        groups.sort_unstable_by_key(|&(label, count)| (std::cmp::Reverse(count), std::cmp::Reverse(self.rank(label).unwrap())));
+
+        // Wildcards always do best by joining the largest group, or by all becoming the strongest
+        // label when the hand is nothing but wildcards
+        let mut signature: Vec<u8> = groups.iter().map(|&(_, count)| count).collect();
+        let wildcards_as = if num_wild == 0 {
+            None
+        } else if let Some(&(label, _)) = groups.first() {
+            signature[0] += num_wild;
+            Some(label)
+        } else {
+            signature.push(num_wild);
+            self.labels.chars().last()
+        };
+
+        Classification {
+            ty: HandType::from_signature(&signature),
+            signature,
+            num_wild,
+            wildcards_as,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -72,154 +108,152 @@ enum HandType {
     FiveKind,
 }
 
+impl HandType {
+    // Group sizes must be sorted from largest to smallest
+    fn from_signature(signature: &[u8]) -> Self {
+        match signature {
+            [5] => Self::FiveKind,
+            [4, 1] => Self::FourKind,
+            [3, 2] => Self::FullHouse,
+            [3, 1, 1] => Self::ThreeKind,
+            [2, 2, 1] => Self::TwoPair,
+            [2, 1, 1, 1] => Self::OnePair,
+            [1, 1, 1, 1, 1] => Self::HighCard,
+
+            // Hands of other sizes are judged by their two largest groups
+            [n, ..] if *n >= 5 => Self::FiveKind,
+            [4, ..] => Self::FourKind,
+            [3, n, ..] if *n >= 2 => Self::FullHouse,
+            [3, ..] => Self::ThreeKind,
+            [2, 2, ..] => Self::TwoPair,
+            [2, ..] => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct Hand {
+struct Classification {
     ty: HandType,
-    cards: Vec<CardLabel>,
-    bid: u64,
+    // Group sizes from largest to smallest, after wildcards have joined the largest group
+    signature: Vec<u8>,
+    num_wild: u8,
+    // Label the wildcards stood in for
+    wildcards_as: Option<char>,
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.ty.cmp(&self.ty).then_with(|| other.compare_cards(self))
-    }
-}
+impl Classification {
+    fn explain(&self) -> String {
+        let groups = self.signature.iter().map(|n| n.to_string()).collect::<Vec<String>>().join("-");
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other)) 
+        match self.wildcards_as {
+            Some(label) => format!("{:?} from groups {} with {} wildcard(s) played as {}", self.ty, groups, self.num_wild, label),
+            None => format!("{:?} from groups {}", self.ty, groups),
+        }
     }
 }
 
-impl Hand {
-    fn new(hand: &str, bid: u64) -> Self {
-        let cards = Self::parse_hand(hand);
-        let ty = Self::determine_hand_type(&cards);
+#[derive(Debug)]
+enum HandError {
+    WrongLength { hand: String, expected: usize, found: usize },
+    InvalidLabel { hand: String, label: char, position: usize },
+    InvalidBid { line: String },
+    KeyTooSmall { hand_size: usize, labels: usize },
+}
 
-        Self {
-            ty,
-            cards,
-            bid,
+impl fmt::Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::WrongLength { hand, expected, found } => write!(f, "Hand {} has {} cards but expected {}", hand, found, expected),
+            Self::InvalidLabel { hand, label, position } => write!(f, "Hand {} has unknown label {:?} at position {}", hand, label, position),
+            Self::InvalidBid { line } => write!(f, "Expected a hand and a bid but found: {}", line),
+            Self::KeyTooSmall { hand_size, labels } => write!(f, "Hands of {} cards from {} labels do not fit in a sort key", hand_size, labels),
         }
     }
+}
+
+// Bits used by the hand type and by each card rank in a hand's sort key
+const HAND_TYPE_BITS: usize = 3;
+const RANK_BITS: usize = 4;
 
-    fn new_with_jokers(hand: &str, bid: u64) -> Self {
-        let cards = Self::parse_hand_with_jokers(hand);
-        let ty = Self::determine_hand_type(&cards);
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Hand {
+    classification: Classification,
+    cards: Vec<u8>,
+    bid: u64,
+    // Hand type followed by each card rank, so hands order by comparing keys
+    key: u32,
+}
 
-        Self {
-            ty,
+impl Hand {
+    // The rules must fit a sort key, which parse_hands checks once for all hands
+    fn new(hand: &str, bid: u64, rules: &Ruleset) -> Result<Self, HandError> {
+        let cards = rules.parse_hand(hand)?;
+        let classification = rules.classify(hand);
+        let key = cards.iter().fold(classification.ty as u32, |key, &rank| (key << RANK_BITS) | rank as u32);
+
+        Ok(Self {
+            classification,
             cards,
             bid,
-        }
+            key,
+        })
     }
+}
 
-    fn parse_hand(hand: &str) -> Vec<CardLabel> {
-        hand.chars().map(|c| CardLabel::from(c).unwrap()).collect()
-    }
-
-    fn parse_hand_with_jokers(hand: &str) -> Vec<CardLabel> {
-        hand.chars().map(|c| CardLabel::from_with_jokers(c).unwrap()).collect()
-    }
-
-    fn determine_hand_type(cards: &Vec<CardLabel>) -> HandType {
-        let mut counts = HashMap::<CardLabel, u8>::new();
-
-        for &card in cards {
-            counts.entry(card).and_modify(|n| { *n += 1 }).or_insert(1);
-        }
-
-        match counts.get(&CardLabel::Joker) {
-            Some(&5) | None => {},
-            Some(&num_jokers) => {
-                counts.remove_entry(&CardLabel::Joker);
-
-                let (&card_label, _) = counts.iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0))).unwrap();
-                counts.entry(card_label).and_modify(|n| { *n += num_jokers });
-            }
-        }
+// Hands from weakest to strongest, paired with their rank starting at 1
+fn rank_hands(mut hands: Vec<Hand>) -> impl Iterator<Item = (u64, Hand)> {
+    hands.sort_unstable_by_key(|hand| hand.key);
+    hands.into_iter().enumerate().map(|(i, hand)| (i as u64 + 1, hand))
+}
 
-        match counts.keys().len() {
-            1 => HandType::FiveKind,
-            2 => {
-                match counts.values().next().unwrap() {
-                    1 | 4 => HandType::FourKind,
-                    2 | 3 => HandType::FullHouse,
-                    _ => panic!("Failed to determine hand with two labels {:?}", cards),
-                }
-            },
-            3 => {
-                for &count in counts.values() {
-                    if count == 3 {
-                        return HandType::ThreeKind;
-                    }
-                }
+fn calculate_winnings(hands: Vec<Hand>) -> u64 {
+    rank_hands(hands).map(|(rank, hand)| rank * hand.bid).sum()
+}
 
-                HandType::TwoPair
-            },
-            4 => HandType::OnePair,
-            5 => HandType::HighCard,
-            _ => panic!("Failed to determine hand {:?}", cards),
-        }
+fn parse_hands(input: &str, rules: &Ruleset) -> Result<Vec<Hand>, HandError> {
+    if !rules.fits_sort_key() {
+        return Err(HandError::KeyTooSmall { hand_size: rules.hand_size, labels: rules.labels.chars().count() });
     }
 
-    fn compare_cards(&self, other: &Hand) -> Ordering {
-        for (this, that) in self.cards.iter().zip(other.cards.iter()) {
-            match this.cmp(that) {
-                Ordering::Equal => continue,
-                ordering => return ordering,
-            }
+    input.lines().map(|line| {
+        match line.split_once(' ').map(|(cards, bid)| (cards, bid.parse::<u64>())) {
+            Some((cards, Ok(bid))) => Hand::new(cards, bid, rules),
+            _ => Err(HandError::InvalidBid { line: line.to_owned() }),
         }
-
-        Ordering::Equal
-    }
+    }).collect()
 }
 
-fn calculate_winnings(hands: Vec<Hand>) -> u64 {
-    // into_sorted_vec undoes the min-ordering and returns high-to-low...
-    // dbg!(BinaryHeap::from(hands).into_sorted_vec());
-
-    // into_iter doesn't handle tie-breakers...
-    // BinaryHeap::from(hands).into_iter().enumerate().fold(0, |acc, (i, hand)| acc + ((i + 1) as u64 * hand.bid))
-
-    let mut heap = BinaryHeap::from(hands);
-    let mut rank = 1;
-    let mut total = 0;
-    while let Some(Hand { ty, cards, bid }) = heap.pop() {
-        println!("Hand: {:?} {:?}", ty, cards);
-        total += rank * bid;
-        rank += 1;
-    }
-
-    total
+fn solve_part_1(input: &str) -> Result<u64, HandError> {
+    parse_hands(input, &STANDARD_RULES).map(calculate_winnings)
 }
 
-
-fn solve_part_1(input: &str) -> u64 {
-    let hands: Vec<Hand> = input.lines().map(|hand| {
-        let (cards, bid) = hand.split_once(' ').unwrap();
-        Hand::new(cards, bid.parse::<u64>().unwrap())
-    }).collect();
-
-    // dbg!(&hands);
-
-    calculate_winnings(hands)
+fn solve_part_2(input: &str) -> Result<u64, HandError> {
+    parse_hands(input, &JOKER_RULES).map(calculate_winnings)
 }
 
-fn solve_part_2(input: &str) -> u64 {
-    let hands: Vec<Hand> = input.lines().map(|hand| {
-        let (cards, bid) = hand.split_once(' ').unwrap();
-        Hand::new_with_jokers(cards, bid.parse::<u64>().unwrap())
-    }).collect();
-
-    // dbg!(&hands);
+pub fn answers() {
+    let input = include_str!("input.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
 
-    calculate_winnings(hands)
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
 }
 
-pub fn answers() {
+pub fn print_hand_types() {
     let input = include_str!("input.txt").trim();
-
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    // let input = include_str!("test.txt").trim();
+
+    for (tag, rules) in [("STANDARD", &STANDARD_RULES), ("JOKER", &JOKER_RULES)] {
+        match parse_hands(input, rules) {
+            Ok(hands) => {
+                for (rank, hand) in rank_hands(hands) {
+                    let cards: String = hand.cards.iter().filter_map(|&card| rules.labels.chars().nth(card as usize)).collect();
+                    println!("[{}] Rank {}: {} is {}", tag, rank, cards, hand.classification.explain());
+                }
+            }
+            Err(e) => eprintln!("[{} :: ERROR] {}", tag, e),
+        }
+    }
 }
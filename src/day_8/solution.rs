@@ -1,131 +1,362 @@
-use std::{str::Lines, collections::HashMap};
+use std::collections::HashMap;
 
-use num::Integer;
-use regex::Regex;
-use once_cell::sync::Lazy;
+use crate::utils::graph::Digraph;
+use crate::utils::math::combine_congruences;
+use crate::utils::parse::{ParseResult, Parser};
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
 
 // https://adventofcode.com/2023/day/8
 
-static NODE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?<node>[A-Z]+) = \((?<left>[A-Z]+), (?<right>[A-Z]+)\)$").unwrap());
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 100,
+    grid: false,
+    allowed: |c| c.is_ascii_uppercase() || c.is_ascii_digit() || " =(),".contains(c),
+};
 
-fn solve_part_1(input: &str) -> u64 {
-    fn construct_network(nodes: &mut Lines) -> HashMap<String, (String, String)> {
-        let mut network = HashMap::<String, (String, String)>::new();
+type NodeId = u32;
 
-        while let Some(node) = nodes.next() {
-            let captures = NODE_RE.captures(node).unwrap();
-            network.insert(captures["node"].to_owned(), (captures["left"].to_owned(), captures["right"].to_owned()));
+// Index into a node's edges: 0 to go left and 1 to go right
+type Direction = usize;
+
+// Nodes are interned in order of first appearance so edges can be followed by indexing
+struct Network<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, NodeId>,
+    edges: Vec<[NodeId; 2]>,
+}
+
+impl<'a> Network<'a> {
+    fn intern(&mut self, name: &'a str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
         }
 
-        network
-    }
+        let id = self.names.len() as NodeId;
+        self.names.push(name);
+        self.ids.insert(name, id);
+        self.edges.push([id, id]);
 
-    fn follow_directions(directions: &str, start: &str, network: &HashMap<String, (String, String)>) -> u64 {
-        let mut steps = 0;
-        let mut directions = directions.chars().cycle();
-        let mut node = start;
+        id
+    }
 
-        loop {
-            steps += 1;
+    fn id(&self, name: &str) -> Result<NodeId, String> {
+        self.ids.get(name).copied().ok_or_else(|| format!("Unknown node: {}", name))
+    }
 
-            let next = network.get(node).unwrap();
-            match directions.next().unwrap() {
-                'L' => {
-                    node = &next.0;
-                }
-                'R' => {
-                    node = &next.1;
-                }
-                otherwise => panic!("Unknown direction: {:?}", otherwise)
-            }
+    fn next(&self, node: NodeId, direction: Direction) -> NodeId {
+        self.edges[node as usize][direction]
+    }
 
-            if node == "ZZZ" {
-                break
-            }
-        }
+    fn nodes_ending_with(&self, suffix: char) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.names.len() as NodeId).filter(move |&id| self.names[id as usize].ends_with(suffix))
+    }
+}
 
-        steps
+// <node> = (<left>, <right>)
+fn parse_node(line: &str) -> ParseResult<[&str; 3]> {
+    fn name<'a>(parser: &mut Parser<'a>) -> ParseResult<&'a str> {
+        parser.take_while("a node name", |c| c.is_ascii_digit() || c.is_ascii_uppercase())
     }
 
+    let mut parser = Parser::new(line);
+
+    let node = name(&mut parser)?;
+    parser.tag(" = (")?;
+    let left = name(&mut parser)?;
+    parser.tag(", ")?;
+    let right = name(&mut parser)?;
+    parser.tag(")")?;
+    parser.end()?;
+
+    Ok([node, left, right])
+}
+
+fn parse_map(input: &str) -> Result<(Vec<Direction>, Network<'_>), String> {
     let mut map = input.lines();
 
-    let directions = map.next().unwrap();
+    let directions = map.next().unwrap_or("").chars().map(|c| match c {
+        'L' => Ok(0),
+        'R' => Ok(1),
+        otherwise => Err(format!("Unknown direction: {:?}", otherwise)),
+    }).collect::<Result<Vec<Direction>, String>>()?;
     map.next(); // Consume empty line between directions and node network
-    let network = construct_network(&mut map);
 
-    follow_directions(directions, &"AAA", &network)
+    let mut network = Network {
+        names: Vec::new(),
+        ids: HashMap::new(),
+        edges: Vec::new(),
+    };
+    let mut defined = Vec::<bool>::new();
+
+    for node in map {
+        let [node, left, right] = parse_node(node).map_err(|e| format!("{} in node: {}", e, node))?;
+
+        let id = network.intern(node);
+        let left = network.intern(left);
+        let right = network.intern(right);
+
+        network.edges[id as usize] = [left, right];
+        defined.resize(network.names.len(), false);
+        defined[id as usize] = true;
+    }
+
+    if let Some(id) = defined.iter().position(|&d| !d) {
+        return Err(format!("Node {} is referenced but never defined", network.names[id]));
+    }
+
+    Ok((directions, network))
 }
 
-fn solve_part_2(input: &str) -> u64 {
-    fn construct_network(nodes: &mut Lines) -> (Vec<String>, HashMap<String, (String, String)>) {
-        let mut start_nodes = Vec::new();
-        let mut network = HashMap::<String, (String, String)>::new();
+// Steps at which a ghost stands on a --Z node. Hits before the ghost settles into its loop happen
+// once, while hits inside the loop repeat every `period` steps.
+#[derive(Debug)]
+struct Cycle {
+    tail: u64,
+    period: u64,
+    tail_hits: Vec<u64>,
+    cycle_hits: Vec<u64>,
+}
 
-        while let Some(node) = nodes.next() {
-            let captures = NODE_RE.captures(node).unwrap();
+impl Cycle {
+    fn is_hit(&self, step: u64) -> bool {
+        if step < self.tail {
+            self.tail_hits.contains(&step)
+        } else {
+            self.cycle_hits.contains(&(self.tail + (step - self.tail) % self.period))
+        }
+    }
+}
 
-            let node = captures["node"].to_owned();
-            let left = captures["left"].to_owned();
-            let right = captures["right"].to_owned();
+// Walks (node, direction index) states until one repeats, which is where the ghost's loop begins
+fn detect_cycle(directions: &[Direction], start: NodeId, network: &Network, is_end: &[bool]) -> Cycle {
+    let mut seen = vec![None; network.names.len() * directions.len()];
+    let mut hits = Vec::new();
+    let mut node = start;
+    let mut step: u64 = 0;
 
-            if node.ends_with("A") {
-                start_nodes.push(node.clone());
-            }
+    loop {
+        let direction_index = step as usize % directions.len();
+        let state = node as usize * directions.len() + direction_index;
+        if let Some(loop_start) = seen[state] {
+            let (tail_hits, cycle_hits) = hits.into_iter().partition(|&hit| hit < loop_start);
 
-            network.insert(node, (left, right));
+            return Cycle {
+                tail: loop_start,
+                period: step - loop_start,
+                tail_hits,
+                cycle_hits,
+            };
         }
+        seen[state] = Some(step);
 
-        (start_nodes, network)
+        if is_end[node as usize] {
+            hits.push(step);
+        }
+
+        node = network.next(node, directions[direction_index]);
+        step += 1;
     }
+}
 
-    fn find_distances_to_z(directions: &str, start_nodes: &Vec<String>, network: &HashMap<String, (String, String)>) -> Vec<u64> {
-        let mut distances = Vec::new();
+fn find_common_step(cycles: &[Cycle]) -> Option<u64> {
+    // A common step before every ghost is looping has to be a tail hit of the ghost with the longest tail
+    let longest_tail = cycles.iter().map(|c| c.tail).max().unwrap_or(0);
+    let early = cycles.iter()
+        .flat_map(|c| c.tail_hits.iter())
+        .filter(|&&step| cycles.iter().all(|c| c.is_hit(step)))
+        .min();
 
-        for node in start_nodes {
-            let mut directions = directions.chars().cycle();
-            let mut current_node = node;
-            let mut steps = 0;
-            
-            while !current_node.ends_with("Z") {
-                steps += 1;
-                match directions.next().unwrap() {
-                    'L' => current_node = &(network.get(current_node).unwrap().0),
-                    'R' => current_node = &(network.get(current_node).unwrap().1),
-                    otherwise => panic!("Unknown direction: {:?}", otherwise)
-                }
-            }
+    if early.is_some() {
+        return early.copied();
+    }
+
+    // Otherwise every ghost is looping, so each combination of loop hits is a system of congruences
+    let mut congruences = vec![(0u128, 1u128)];
+    for cycle in cycles {
+        congruences = congruences.iter().flat_map(|&congruence| {
+            cycle.cycle_hits.iter().filter_map(move |&hit| combine_congruences(congruence, ((hit % cycle.period) as u128, cycle.period as u128)))
+        }).collect();
 
-            distances.push(steps);
+        congruences.sort_unstable();
+        congruences.dedup();
+    }
+
+    congruences.iter().map(|&(residue, modulus)| {
+        let longest_tail = longest_tail as u128;
+        if residue >= longest_tail {
+            residue
+        } else {
+            residue + (longest_tail - residue).div_ceil(modulus) * modulus
         }
+    }).min().and_then(|step| u64::try_from(step).ok())
+}
 
-        distances
+fn solve_part_1(input: &str) -> Result<u64, String> {
+    let (directions, network) = parse_map(input)?;
+
+    let end = network.id("ZZZ")?;
+    let mut node = network.id("AAA")?;
+    let mut steps = 0;
+
+    for &direction in directions.iter().cycle() {
+        if node == end {
+            break;
+        }
+
+        node = network.next(node, direction);
+        steps += 1;
     }
 
-    let mut map = input.lines();
+    Ok(steps)
+}
 
-    let directions = map.next().unwrap();
-    map.next(); // Consume empty line between directions and node network
-    let (start_nodes, network) = construct_network(&mut map);
-
-    /*
-    * Problem statement is very poorly worded and does not state any crucial assumptions that must
-    * be made in order for least common multiple to be a tractable approach.
-    * ---
-    * """
-    * Nothing in the problem statement guarantees that any of this would be true, but all the provided inputs for this problem have special properties even if they were not explicitly spelled out:
-    * - Each --A node only reaches one --Z node in it's loop
-    * - they all reach their --Z at the same "step" in the directions every time, meaning the loops are all a consistent period instead of changing or branching
-    * - Conveniently the period it takes to reach the first --Z from the starting --A node is the same period as it takes to re-reach the --Z node when you're already there.
-    * """
-    * See: https://www.reddit.com/r/adventofcode/comments/18dfpub/2023_day_8_part_2_why_is_spoiler_correct/
-    */
-    find_distances_to_z(directions, &start_nodes, &network).iter().fold(1, |acc, d| acc.lcm(d))
+fn solve_part_2(input: &str) -> Result<u64, String> {
+    let (directions, network) = parse_map(input)?;
+
+    let mut is_end = vec![false; network.names.len()];
+    network.nodes_ending_with('Z').for_each(|id| is_end[id as usize] = true);
+
+    let cycles: Vec<Cycle> = network.nodes_ending_with('A').map(|node| detect_cycle(&directions, node, &network, &is_end)).collect();
+
+    find_common_step(&cycles).ok_or_else(|| "Ghosts never stand on --Z nodes at the same time".to_owned())
+}
+
+fn network_to_dot(network: &Network) -> String {
+    let mut graph = Digraph::new("network");
+
+    for &name in &network.names {
+        if name.ends_with('A') {
+            graph.node(name, &[("style", "filled"), ("fillcolor", "palegreen")]);
+        } else if name.ends_with('Z') {
+            graph.node(name, &[("style", "filled"), ("fillcolor", "lightcoral")]);
+        } else {
+            graph.node(name, &[]);
+        }
+    }
+
+    for (id, &[left, right]) in network.edges.iter().enumerate() {
+        let name = network.names[id];
+
+        if left == right {
+            graph.edge(name, network.names[left as usize], &[("label", "L/R")]);
+        } else {
+            graph.edge(name, network.names[left as usize], &[("label", "L")]);
+            graph.edge(name, network.names[right as usize], &[("label", "R")]);
+        }
+    }
+
+    graph.to_dot()
 }
 
 pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+pub fn export_dot() {
     let input = include_str!("input.txt").trim();
     // let input = include_str!("test.txt").trim();
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    let network = match parse_map(input) {
+        Ok((_, network)) => network,
+        Err(e) => {
+            eprintln!("[DOT :: ERROR] {}", e);
+            return;
+        }
+    };
+    print!("{}", network_to_dot(&network));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // First step on which every ghost stands on a --Z node, found by walking them all, or None when
+    // there is none within `limit` steps
+    fn brute_force(input: &str, limit: u64) -> Option<u64> {
+        let (directions, network) = parse_map(input).unwrap();
+        let mut ghosts: Vec<NodeId> = network.nodes_ending_with('A').collect();
+
+        for step in 0..limit {
+            if ghosts.iter().all(|&ghost| network.names[ghost as usize].ends_with('Z')) {
+                return Some(step);
+            }
+
+            let direction = directions[step as usize % directions.len()];
+            ghosts.iter_mut().for_each(|ghost| *ghost = network.next(*ghost, direction));
+        }
+
+        None
+    }
+
+    #[test]
+    fn tail_hit_before_the_loop() {
+        // 11A only stands on 11Z at step 1, before it settles into the 11B/11C loop
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (11C, 11C)\n11C = (11B, 11B)\n22A = (22Z, 22Z)\n22Z = (22A, 22A)";
+
+        assert_eq!(solve_part_2(input).ok(), Some(1));
+        assert_eq!(brute_force(input, 100), Some(1));
+    }
+
+    #[test]
+    fn several_hits_in_one_loop() {
+        // 11A loops every 5 steps with hits at 1 and 3 (mod 5) and 22A every 3 steps with a hit at 2 (mod 3),
+        // so only the second of 11A's hits lines up first
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (13Z, 13Z)\n13Z = (11C, 11C)\n11C = (11A, 11A)\n22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22A, 22A)";
+
+        assert_eq!(solve_part_2(input).ok(), Some(8));
+        assert_eq!(brute_force(input, 100), Some(8));
+    }
+
+    #[test]
+    fn ghosts_that_never_line_up() {
+        // 11A is on a --Z node on odd steps and 22A on even steps
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22B, 22B)";
+
+        assert!(solve_part_2(input).is_err());
+        assert_eq!(brute_force(input, 1000), None);
+    }
+
+    #[test]
+    fn malformed_maps() {
+        assert_eq!(solve_part_1("LX\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)"), Err("Unknown direction: 'X'".to_owned()));
+        assert_eq!(solve_part_1("L\n\nAAA = (ZZZ, ZZZ)"), Err("Node ZZZ is referenced but never defined".to_owned()));
+        assert_eq!(solve_part_1("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)"), Err("Unknown node: ZZZ".to_owned()));
+        assert!(solve_part_2("L\n\nAAA = (BBB BBB)").is_err());
+    }
+
+    #[test]
+    fn random_networks_match_brute_force() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut random = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % n
+        };
+
+        for _ in 0..500 {
+            let nodes = 2 + random(6) as usize;
+            let names: Vec<String> = (0..nodes).map(|i| format!("N{}{}", i, ['A', 'B', 'Z'][if i == 0 { 0 } else { random(3) as usize }])).collect();
+            let directions: String = (0..1 + random(3)).map(|_| if random(2) == 0 { 'L' } else { 'R' }).collect();
+
+            let mut input = format!("{}\n", directions);
+            for name in &names {
+                input += &format!("\n{} = ({}, {})", name, names[random(nodes as u64) as usize], names[random(nodes as u64) as usize]);
+            }
+
+            // Every ghost loops within nodes * directions steps, so a common step, if any, comes well before the limit
+            let limit = 10_000;
+            match brute_force(&input, limit) {
+                Some(step) => assert_eq!(solve_part_2(&input).ok(), Some(step), "{}", input),
+                None => assert!(solve_part_2(&input).map_or(true, |step| step >= limit), "{}", input),
+            }
+        }
+    }
 }
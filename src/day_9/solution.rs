@@ -1,64 +1,131 @@
+use std::fmt;
+
+use crate::utils::lines::sum_lines;
+use crate::utils::input::{check_input, InputSpec};
+use crate::utils::print::print_answer;
+
 // https://adventofcode.com/2023/day/9
 
-enum ExtrapolationDirection {
-    Previous,
-    Next
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 50,
+    grid: false,
+    allowed: |c| c.is_ascii_digit() || " -".contains(c),
+};
+
+#[derive(Debug)]
+enum ExtrapolationError {
+    Empty,
+    // The differences never reach a row of all zeros, so the polynomial is not pinned down
+    Underdetermined { length: usize },
+    Overflow,
 }
 
-fn calculate_differences(nums: Vec<i64>, values_to_keep: &ExtrapolationDirection) -> Vec<i64> {
-    let mut differences = nums;
-    let mut values = Vec::new();
+impl fmt::Display for ExtrapolationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Cannot extrapolate an empty sequence"),
+            Self::Underdetermined { length } => write!(f, "Differences of {} values never reach all zeros", length),
+            Self::Overflow => write!(f, "Extrapolated value does not fit in an i128"),
+        }
+    }
+}
 
-    loop {
-        let mut it = differences.iter();
-        let mut current_value = *it.next().unwrap();
-        let mut next_differences = Vec::new();
+// Polynomial through a sequence sampled at indices 0, 1, 2, ..., kept in Newton forward-difference
+// form: value(x) = sum over k of (k-th difference of the first value) * C(x, k)
+#[derive(Debug)]
+struct Sequence {
+    differences: Vec<i128>,
+    length: usize,
+}
 
-        if matches!(values_to_keep, ExtrapolationDirection::Previous) {
-            values.push(current_value);
+impl Sequence {
+    fn fit(nums: &[i64]) -> Result<Self, ExtrapolationError> {
+        if nums.is_empty() {
+            return Err(ExtrapolationError::Empty);
         }
 
-        while let Some(&value) = it.next() {
-            next_differences.push(value - current_value);
-            current_value = value;
-        }
+        // First value of each row of the difference table, stopping at the first row of all zeros
+        let mut differences = Vec::with_capacity(nums.len());
+        let mut row: Vec<i128> = nums.iter().map(|&n| n as i128).collect();
 
-        if matches!(values_to_keep, ExtrapolationDirection::Next) {
-            values.push(current_value);
-        }
+        while row.iter().any(|&d| d != 0) {
+            if row.len() == 1 {
+                return Err(ExtrapolationError::Underdetermined { length: nums.len() });
+            }
 
-        if next_differences.iter().all(|&x| x == 0) {
-            break;
+            differences.push(row[0]);
+            row = row.windows(2).map(|pair| pair[1].checked_sub(pair[0])).collect::<Option<Vec<i128>>>().ok_or(ExtrapolationError::Overflow)?;
         }
 
-        differences = next_differences;
+        Ok(Self {
+            differences,
+            length: nums.len(),
+        })
     }
 
-    values
-}
+    // Degree of the fitted polynomial, counting a constant sequence, including all zeros, as degree 0
+    fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    fn value_at(&self, index: i128) -> Result<i128, ExtrapolationError> {
+        let mut value: i128 = 0;
+        let mut binomial: i128 = 1;
 
-fn extrapolate_value(line: &str, direction: ExtrapolationDirection) -> i64 {
-    let nums = line.split(' ').map(|c| c.parse::<i64>().unwrap()).collect::<Vec<i64>>();
-    let last_values = calculate_differences(nums, &direction);
+        for (k, &difference) in self.differences.iter().enumerate() {
+            let term = difference.checked_mul(binomial).ok_or(ExtrapolationError::Overflow)?;
+            value = value.checked_add(term).ok_or(ExtrapolationError::Overflow)?;
 
-    match direction {
-        ExtrapolationDirection::Next => last_values.iter().rev().fold(0, |acc, x| acc + x),
-        ExtrapolationDirection::Previous => last_values.iter().rev().fold(0, |acc, x| x - acc),
+            // C(x, k + 1) = C(x, k) * (x - k) / (k + 1), which divides exactly for any integer x
+            binomial = binomial.checked_mul(index - k as i128).ok_or(ExtrapolationError::Overflow)? / (k + 1) as i128;
+        }
+
+        Ok(value)
+    }
+
+    fn next(&self, steps: i128) -> Result<i128, ExtrapolationError> {
+        self.value_at(self.length as i128 - 1 + steps)
     }
+
+    fn previous(&self, steps: i128) -> Result<i128, ExtrapolationError> {
+        self.value_at(-steps)
+    }
+}
+
+fn parse_sequence(line: &str) -> Vec<i64> {
+    line.split_whitespace().map(|c| c.parse::<i64>().unwrap()).collect()
 }
 
-fn solve_part_1(input: &str) -> i64 {
-    input.lines().map(|l| extrapolate_value(l, ExtrapolationDirection::Next)).sum()
+fn solve_part_1(input: &str) -> Result<i128, String> {
+    sum_lines(input, |l| {
+        Sequence::fit(&parse_sequence(l)).and_then(|sequence| sequence.next(1)).map_err(|e| format!("{} in line: {}", e, l))
+    })
 }
 
-fn solve_part_2(input: &str) -> i64 {
-    input.lines().map(|l| extrapolate_value(l, ExtrapolationDirection::Previous)).sum()
+fn solve_part_2(input: &str) -> Result<i128, String> {
+    sum_lines(input, |l| {
+        Sequence::fit(&parse_sequence(l)).and_then(|sequence| sequence.previous(1)).map_err(|e| format!("{} in line: {}", e, l))
+    })
 }
 
 pub fn answers() {
+    let input = include_str!("input.txt");
+    // let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
+
+    print_answer("P1", solve_part_1(input));
+    print_answer("P2", solve_part_2(input));
+}
+
+pub fn print_degrees() {
     let input = include_str!("input.txt").trim();
     // let input = include_str!("test.txt").trim();
 
-    println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
-    println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
+    for (i, line) in input.lines().enumerate() {
+        match Sequence::fit(&parse_sequence(line)).and_then(|sequence| Ok((sequence.degree(), sequence.previous(1)?, sequence.next(1)?))) {
+            Ok((degree, previous, next)) => println!("Line {}: degree {}, previous {}, next {}", i + 1, degree, previous, next),
+            Err(e) => eprintln!("[DEGREES :: ERROR] {} in line {}: {}", e, i + 1, line),
+        }
+    }
 }
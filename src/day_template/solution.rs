@@ -1,5 +1,13 @@
+use crate::utils::input::{check_input, InputSpec};
+
 // https://adventofcode.com/2023/day/CHANGEME
 
+static INPUT_SPEC: InputSpec = InputSpec {
+    min_lines: 0,
+    grid: false,
+    allowed: |_| true,
+};
+
 fn solve_part_1(input: &str) -> u64 {
     todo!();
 }
@@ -9,8 +17,10 @@ fn solve_part_2(input: &str) -> u64 {
 }
 
 pub fn answers() {
-    // let input = include_str!("input.txt").trim();
-    let input = include_str!("test.txt").trim();
+    // let input = include_str!("input.txt");
+    let input = include_str!("test.txt");
+    check_input(input, Some(include_str!("test.txt")), &INPUT_SPEC);
+    let input = input.trim();
 
     println!("[P1 :: INFO] Answer: {}", solve_part_1(input));
     println!("[P2 :: INFO] Answer: {}", solve_part_2(input));
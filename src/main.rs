@@ -4,40 +4,87 @@ mod day_1;
 mod day_2;
 mod day_3;
 mod day_4;
+mod day_5;
 
 mod day_6;
 mod day_7;
 mod day_8;
 mod day_9;
+mod day_10;
 
 mod day_11;
 mod day_12;
 mod day_13;
-
+mod day_14;
 mod day_15;
+
 mod day_16;
 mod day_17;
-
+mod day_18;
 mod day_19;
+mod day_20;
+
+mod day_21;
+mod day_22;
+mod day_23;
+mod day_24;
+mod day_25;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.as_slice() {
+        [] => {}
+        [command, part] if command == "trace-part" => {
+            if !day_19::solution::print_part_trace(part) {
+                std::process::exit(1);
+            }
+            return;
+        }
+        _ => {
+            eprintln!("Usage: aoc-2023 [trace-part <part>]");
+            eprintln!("  trace-part    Show how the day 19 workflows handle a part, e.g. {{x=787,m=2655,a=1222,s=2876}}");
+            std::process::exit(2);
+        }
+    }
+
     // day_1::solution::answers();
     // day_2::solution::answers();
     // day_3::solution::answers();
     // day_4::solution::answers();
+    // day_5::solution::answers();
 
     // day_6::solution::answers();
     // day_7::solution::answers();
     // day_8::solution::answers();
     // day_9::solution::answers();
+    // day_10::solution::answers();
 
     // day_11::solution::answers();
     // day_12::solution::answers();
     // day_13::solution::answers();
-
+    // day_14::solution::answers();
     // day_15::solution::answers();
+
     // day_16::solution::answers();
     // day_17::solution::answers();
-
+    // day_18::solution::answers();
     day_19::solution::answers();
+    // day_20::solution::answers();
+
+    // day_21::solution::answers();
+    // day_22::solution::answers();
+    // day_23::solution::answers();
+    // day_24::solution::answers();
+    // day_25::solution::answers();
+
+    // day_3::solution::print_symbol_report();
+    // day_4::solution::print_copies();
+    // day_7::solution::print_hand_types();
+    // day_8::solution::export_dot();
+    // day_9::solution::print_degrees();
+    // day_15::solution::print_history();
+    // day_19::solution::export_dot();
+    // day_19::solution::analyze();
+    // day_19::solution::simplify();
 }
@@ -0,0 +1,59 @@
+// Builds directed graphs and renders them in Graphviz DOT format, e.g. `dot -Tsvg graph.dot`
+
+type Attributes = Vec<(&'static str, String)>;
+
+pub struct Digraph {
+    name: String,
+    nodes: Vec<(String, Attributes)>,
+    edges: Vec<(String, String, Attributes)>,
+}
+
+impl Digraph {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn node(&mut self, id: &str, attributes: &[(&'static str, &str)]) {
+        self.nodes.push((id.to_owned(), Self::to_attributes(attributes)));
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&'static str, &str)]) {
+        self.edges.push((from.to_owned(), to.to_owned(), Self::to_attributes(attributes)));
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", quote(&self.name));
+
+        for (id, attributes) in &self.nodes {
+            dot.push_str(&format!("    {}{};\n", quote(id), format_attributes(attributes)));
+        }
+
+        for (from, to, attributes) in &self.edges {
+            dot.push_str(&format!("    {} -> {}{};\n", quote(from), quote(to), format_attributes(attributes)));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_attributes(attributes: &[(&'static str, &str)]) -> Attributes {
+        attributes.iter().map(|&(key, value)| (key, value.to_owned())).collect()
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn format_attributes(attributes: &Attributes) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+
+    let attributes: Vec<String> = attributes.iter().map(|(key, value)| format!("{}={}", key, quote(value))).collect();
+    format!(" [{}]", attributes.join(", "))
+}
@@ -0,0 +1,185 @@
+// Sanity checks run on a puzzle input before solving, so that a pasted example, Windows line endings or
+// stray whitespace show up as warnings instead of as a confusing answer
+
+use std::fmt;
+
+// What a day's real input looks like
+pub struct InputSpec {
+    // Real inputs have at least this many lines, so fewer usually means an example was pasted in
+    pub min_lines: usize,
+    // Every line within a block of lines has the same width, where blocks are separated by blank lines
+    pub grid: bool,
+    // Characters that may appear apart from line breaks
+    pub allowed: fn(char) -> bool,
+}
+
+// Shape of an input as read from disk, before any trimming
+#[derive(Debug)]
+struct Fingerprint<'a> {
+    // Lines without their line endings, not counting the empty line after a final line break
+    lines: Vec<&'a str>,
+    crlf_lines: Vec<usize>,
+}
+
+impl<'a> Fingerprint<'a> {
+    fn of(input: &'a str) -> Self {
+        let mut lines: Vec<&str> = input.split('\n').collect();
+        if input.ends_with('\n') {
+            lines.pop();
+        }
+
+        let mut crlf_lines = Vec::new();
+        for (i, line) in lines.iter_mut().enumerate() {
+            if let Some(stripped) = line.strip_suffix('\r') {
+                *line = stripped;
+                crlf_lines.push(i + 1);
+            }
+        }
+
+        Self {
+            lines,
+            crlf_lines,
+        }
+    }
+
+    // Lines without surrounding blank lines, which is what solutions see after trimming the input
+    fn content_lines(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        let first = self.lines.iter().position(|line| !line.trim().is_empty()).unwrap_or(self.lines.len());
+        let last = self.lines.iter().rposition(|line| !line.trim().is_empty()).map_or(first, |i| i + 1);
+
+        (first..last).map(|i| (i + 1, self.lines[i]))
+    }
+}
+
+#[derive(Debug)]
+pub enum Warning {
+    Empty,
+    // The input is the day's test.txt
+    Example,
+    CarriageReturns { first_line: usize, lines: usize },
+    TrailingWhitespace { first_line: usize, lines: usize },
+    TooFewLines { lines: usize, expected: usize },
+    RaggedGrid { line: usize, width: usize, expected: usize },
+    UnexpectedCharacter { line: usize, column: usize, character: char, count: usize },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The input is empty"),
+            Self::Example => write!(f, "The input is the example from test.txt, so the answers are not for the real puzzle"),
+            Self::CarriageReturns { first_line, lines } => write!(f, "{} line(s) end with CRLF, starting at line {}", lines, first_line),
+            Self::TrailingWhitespace { first_line, lines } => write!(f, "{} line(s) have trailing whitespace, starting at line {}", lines, first_line),
+            Self::TooFewLines { lines, expected } => write!(f, "Only {} line(s) where real inputs have at least {}, is this an example?", lines, expected),
+            Self::RaggedGrid { line, width, expected } => write!(f, "Line {} is {} wide but the lines before it are {} wide", line, width, expected),
+            Self::UnexpectedCharacter { line, column, character, count } => write!(f, "Unexpected {:?} at line {}, column {} ({} in total)", character, line, column, count),
+        }
+    }
+}
+
+// Everything about the input that does not match the spec, comparing it with the example when there is one
+pub fn check(input: &str, example: Option<&str>, spec: &InputSpec) -> Vec<Warning> {
+    if input.trim().is_empty() {
+        return vec![Warning::Empty];
+    }
+
+    let mut warnings = Vec::new();
+    let fingerprint = Fingerprint::of(input);
+    let lines: Vec<(usize, &str)> = fingerprint.content_lines().collect();
+
+    // Compared line by line so that line endings and blank lines around the text do not matter
+    let is_example = example.is_some_and(|example| {
+        Fingerprint::of(example).content_lines().map(|(_, line)| line).eq(lines.iter().map(|&(_, line)| line))
+    });
+    if is_example {
+        warnings.push(Warning::Example);
+    } else if lines.len() < spec.min_lines {
+        warnings.push(Warning::TooFewLines { lines: lines.len(), expected: spec.min_lines });
+    }
+
+    if let Some(&first_line) = fingerprint.crlf_lines.first() {
+        warnings.push(Warning::CarriageReturns { first_line, lines: fingerprint.crlf_lines.len() });
+    }
+
+    let trailing: Vec<usize> = lines.iter().filter(|(_, line)| line.ends_with(char::is_whitespace)).map(|&(i, _)| i).collect();
+    if let Some(&first_line) = trailing.first() {
+        warnings.push(Warning::TrailingWhitespace { first_line, lines: trailing.len() });
+    }
+
+    if spec.grid {
+        // Width of the first line of the current block, reset by blank lines
+        let mut expected: Option<usize> = None;
+
+        for &(line, text) in &lines {
+            let width = text.trim_end().chars().count();
+
+            match expected {
+                _ if width == 0 => expected = None,
+                None => expected = Some(width),
+                Some(expected) if width != expected => {
+                    warnings.push(Warning::RaggedGrid { line, width, expected });
+                    break;
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    let mut unexpected = lines.iter().flat_map(|&(line, text)| {
+        text.trim_end().chars().enumerate().filter(|&(_, c)| !(spec.allowed)(c)).map(move |(column, c)| (line, column + 1, c))
+    });
+
+    if let Some((line, column, character)) = unexpected.next() {
+        warnings.push(Warning::UnexpectedCharacter { line, column, character, count: unexpected.count() + 1 });
+    }
+
+    warnings
+}
+
+// Prints a warning for everything about the input that does not match the spec
+pub fn check_input(input: &str, example: Option<&str>, spec: &InputSpec) {
+    for warning in check(input, example, spec) {
+        eprintln!("[INPUT :: WARN] {}", warning);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static GRID: InputSpec = InputSpec {
+        min_lines: 3,
+        grid: true,
+        allowed: |c| ".#".contains(c),
+    };
+
+    #[test]
+    fn recognises_the_example_whatever_its_line_endings() {
+        let example = "..#\n#..\n.#.\n";
+
+        assert!(matches!(check(example, Some(example), &GRID)[..], [Warning::Example]));
+        assert!(matches!(check("\n..#\n#..\n.#.", Some(example), &GRID)[..], [Warning::Example]));
+        assert!(matches!(
+            check("..#\r\n#..\r\n.#.\r\n", Some(example), &GRID)[..],
+            [Warning::Example, Warning::CarriageReturns { first_line: 1, lines: 3 }]
+        ));
+        assert!(check("..#\n#..\n..#\n", Some(example), &GRID).is_empty());
+    }
+
+    #[test]
+    fn reports_problems_with_their_lines() {
+        assert!(matches!(check(" \n\n", None, &GRID)[..], [Warning::Empty]));
+        assert!(matches!(check("..#\n#..\n", None, &GRID)[..], [Warning::TooFewLines { lines: 2, expected: 3 }]));
+        assert!(matches!(check("..#\n#.. \n.#.\n", None, &GRID)[..], [Warning::TrailingWhitespace { first_line: 2, lines: 1 }]));
+        assert!(matches!(check("..#\n#...\n.#.\n", None, &GRID)[..], [Warning::RaggedGrid { line: 2, width: 4, expected: 3 }]));
+        assert!(matches!(
+            check("..#\n#x.\n.#x\n", None, &GRID)[..],
+            [Warning::UnexpectedCharacter { line: 2, column: 2, character: 'x', count: 2 }]
+        ));
+    }
+
+    #[test]
+    fn grid_width_resets_after_blank_lines() {
+        assert!(check("..#\n#..\n\n.#\n#.\n", None, &GRID).is_empty());
+    }
+}
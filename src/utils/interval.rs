@@ -0,0 +1,341 @@
+// Integer intervals, sets of disjoint intervals and boxes built from one interval per axis
+
+use num::PrimInt;
+
+// Non-empty run of consecutive integers. It is stored as its first and last values so that an
+// interval can end at T::MAX, and constructors return None instead of building an empty one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    // start..=end
+    pub fn closed(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    // start..end
+    pub fn half_open(start: T, end: T) -> Option<Self> {
+        (start < end).then(|| Self { start, end: end - T::one() })
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    // Last value in the interval
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    // Saturates at u128::MAX for all of u128 or i128, which have one more value than a u128 can count
+    pub fn len(&self) -> u128 {
+        let span = match (self.start.to_u128(), self.end.to_u128()) {
+            (Some(start), Some(end)) => end - start,
+            _ => self.end.to_i128().unwrap().abs_diff(self.start.to_i128().unwrap()),
+        };
+
+        span.saturating_add(1)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::closed(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // Values below `point` and values from `point` upwards
+    pub fn split_at(&self, point: T) -> (Option<Self>, Option<Self>) {
+        if point <= self.start {
+            (None, Some(*self))
+        } else if point > self.end {
+            (Some(*self), None)
+        } else {
+            (Some(Self { start: self.start, end: point - T::one() }), Some(Self { start: point, end: self.end }))
+        }
+    }
+}
+
+// Union of intervals, kept sorted with overlapping and touching intervals merged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    fn normalize(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.sort_unstable_by_key(|interval| interval.start);
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.end == T::max_value() || interval.start <= last.end + T::one() => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+
+        Self {
+            intervals: merged,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // Number of values in the set
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(|interval| interval.len()).sum()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals.iter().any(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        self.extend([interval]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.intervals.iter().chain(&other.intervals).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (&self.intervals[i], &other.intervals[j]);
+            intervals.extend(a.intersection(b));
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self {
+            intervals,
+        }
+    }
+
+    // Values in this set that are not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        for interval in &self.intervals {
+            // First value not yet covered or removed, or None once the rest of the interval is gone
+            let mut next = Some(interval.start);
+
+            for removed in other.intervals.iter().filter(|removed| removed.end >= interval.start && removed.start <= interval.end) {
+                let Some(start) = next else { break };
+
+                if start < removed.start {
+                    intervals.push(Interval { start, end: removed.start - T::one() });
+                }
+
+                next = removed.end.checked_add(&T::one());
+            }
+
+            intervals.extend(next.and_then(|start| Interval::closed(start, interval.end)));
+        }
+
+        Self {
+            intervals,
+        }
+    }
+
+    // Values below `point` and values from `point` upwards
+    pub fn split_at(&self, point: T) -> (Self, Self) {
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+
+        for interval in &self.intervals {
+            let (left, right) = interval.split_at(point);
+            below.extend(left);
+            above.extend(right);
+        }
+
+        (Self { intervals: below }, Self { intervals: above })
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self {
+            intervals: vec![interval],
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        Self::normalize(intervals.into_iter().collect())
+    }
+}
+
+impl<T: PrimInt> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, intervals: I) {
+        let mut all = std::mem::take(&mut self.intervals);
+        all.extend(intervals);
+        *self = Self::normalize(all);
+    }
+}
+
+// Cartesian product of one interval per axis
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalBox<T> {
+    sides: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalBox<T> {
+    pub fn new(sides: Vec<Interval<T>>) -> Self {
+        Self {
+            sides,
+        }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.sides.len()
+    }
+
+    pub fn side(&self, axis: usize) -> Interval<T> {
+        self.sides[axis]
+    }
+
+    // Copy of the box with one side replaced
+    pub fn with_side(&self, axis: usize, side: Interval<T>) -> Self {
+        let mut sides = self.sides.clone();
+        sides[axis] = side;
+
+        Self {
+            sides,
+        }
+    }
+
+    // Number of integer points inside the box
+    pub fn volume(&self) -> u128 {
+        self.sides.iter().map(|side| side.len()).product()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let sides = self.sides.iter().zip(&other.sides).map(|(a, b)| a.intersection(b)).collect::<Option<Vec<Interval<T>>>>()?;
+
+        Some(Self {
+            sides,
+        })
+    }
+
+    // Parts of the box on either side of `point` along one axis
+    pub fn split_at(&self, axis: usize, point: T) -> (Option<Self>, Option<Self>) {
+        let (below, above) = self.sides[axis].split_at(point);
+
+        (below.map(|side| self.with_side(axis, side)), above.map(|side| self.with_side(axis, side)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    // Values the brute-force checks draw from, including both ends of the type
+    const VALUES: [u8; 8] = [0, 1, 2, 3, 252, 253, 254, 255];
+
+    // Every subset of VALUES, built one value at a time so that merging is exercised too
+    fn subsets() -> impl Iterator<Item = (BTreeSet<u8>, IntervalSet<u8>)> {
+        (0..1 << VALUES.len()).map(|mask: u32| {
+            let values: BTreeSet<u8> = VALUES.iter().enumerate().filter(|&(i, _)| mask & (1 << i) != 0).map(|(_, &v)| v).collect();
+            let set = values.iter().map(|&v| Interval::closed(v, v).unwrap()).collect();
+
+            (values, set)
+        })
+    }
+
+    // Values in the set, after checking that its intervals are sorted and neither overlap nor touch
+    fn values(set: &IntervalSet<u8>) -> BTreeSet<u8> {
+        let intervals: Vec<&Interval<u8>> = set.iter().collect();
+        for pair in intervals.windows(2) {
+            assert!((pair[0].end() as u16) + 1 < pair[1].start() as u16, "{:?} is not normalized", set);
+        }
+
+        intervals.iter().flat_map(|interval| interval.start()..=interval.end()).collect()
+    }
+
+    #[test]
+    fn constructors_reject_empty_intervals() {
+        assert_eq!(Interval::closed(3, 2), None);
+        assert_eq!(Interval::half_open(3, 3), None);
+        assert_eq!(Interval::half_open(3, 4), Interval::closed(3, 3));
+    }
+
+    #[test]
+    fn len_spans_whole_types() {
+        assert_eq!(Interval::closed(0u8, u8::MAX).unwrap().len(), 256);
+        assert_eq!(Interval::closed(i8::MIN, i8::MAX).unwrap().len(), 256);
+        assert_eq!(Interval::closed(-5i64, 5).unwrap().len(), 11);
+        assert_eq!(Interval::closed(i128::MIN, i128::MAX).unwrap().len(), u128::MAX);
+        assert_eq!(Interval::closed(0u128, u128::MAX).unwrap().len(), u128::MAX);
+    }
+
+    #[test]
+    fn set_operations_match_brute_force() {
+        let subsets: Vec<(BTreeSet<u8>, IntervalSet<u8>)> = subsets().collect();
+
+        for (a_values, a) in &subsets {
+            assert_eq!(&values(a), a_values);
+            assert_eq!(a.len(), a_values.len() as u128);
+
+            for (b_values, b) in &subsets {
+                assert_eq!(values(&a.union(b)), a_values | b_values);
+                assert_eq!(values(&a.intersection(b)), a_values & b_values);
+                assert_eq!(values(&a.difference(b)), a_values - b_values);
+            }
+        }
+    }
+
+    #[test]
+    fn split_matches_brute_force() {
+        for (set_values, set) in subsets() {
+            for point in VALUES {
+                let (below, above) = set.split_at(point);
+
+                assert_eq!(values(&below), set_values.iter().copied().filter(|&v| v < point).collect());
+                assert_eq!(values(&above), set_values.iter().copied().filter(|&v| v >= point).collect());
+            }
+        }
+    }
+
+    #[test]
+    fn box_volume_and_split() {
+        let cube = IntervalBox::new(vec![Interval::closed(1u64, 4000).unwrap(); 4]);
+        assert_eq!(cube.volume(), 4000u128.pow(4));
+
+        let (below, above) = cube.split_at(2, 1001);
+        assert_eq!(below.as_ref().map(IntervalBox::volume), Some(4000u128.pow(3) * 1000));
+        assert_eq!(above.as_ref().map(IntervalBox::volume), Some(4000u128.pow(3) * 3000));
+        assert_eq!(cube.split_at(0, 1), (None, Some(cube.clone())));
+        assert_eq!(below.unwrap().intersection(&above.unwrap()), None);
+    }
+}
@@ -0,0 +1,29 @@
+// Sums a per-line computation over the input. With the `parallel` feature the lines are split into
+// contiguous chunks that are evaluated on separate threads, and the chunk totals are added back in
+// line order so the result matches the sequential version.
+pub fn sum_lines<T, F>(input: &str, f: F) -> T
+where
+    T: std::iter::Sum<T> + Send,
+    F: Fn(&str) -> T + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        let lines: Vec<&str> = input.lines().collect();
+        let num_threads = std::thread::available_parallelism().map_or(1, |n| n.get()).min(lines.len());
+
+        if num_threads > 1 {
+            let chunk_size = lines.len().div_ceil(num_threads);
+            let f = &f;
+
+            return std::thread::scope(|scope| {
+                let handles: Vec<_> = lines.chunks(chunk_size).map(|chunk| {
+                    scope.spawn(move || chunk.iter().map(|&line| f(line)).sum::<T>())
+                }).collect();
+
+                handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+            });
+        }
+    }
+
+    input.lines().map(f).sum()
+}
@@ -0,0 +1,262 @@
+// Number theory helpers for non-negative integers of any primitive type. Modular arithmetic is done in
+// u128, multiplying by doubling whenever a product would not fit, so only the results have to fit in T.
+
+use num::{PrimInt, Signed};
+
+pub fn gcd<T: PrimInt>(mut a: T, mut b: T) -> T {
+    while b != T::zero() {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+// Least common multiple, or None when it does not fit in T
+pub fn lcm<T: PrimInt>(a: T, b: T) -> Option<T> {
+    if a == T::zero() || b == T::zero() {
+        return Some(T::zero());
+    }
+
+    (a / gcd(a, b)).checked_mul(&b)
+}
+
+// Returns (g, x, y) with g = gcd(a, b) and a * x + b * y = g
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (T::one(), T::zero());
+    let (mut y0, mut y1) = (T::zero(), T::one());
+
+    while r1 != T::zero() {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < T::zero() {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+// a * b % m for a, b < m
+fn mul_mod(mut a: u128, mut b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a, m);
+        }
+
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+
+    product
+}
+
+// x with a * x = 1 (mod m), found by running Euclid's algorithm with the coefficient kept reduced mod m.
+// None when a and m share a factor.
+fn inverse_u128(a: u128, m: u128) -> Option<u128> {
+    if m == 1 {
+        return Some(0);
+    }
+
+    let (mut r0, mut r1) = (m, a % m);
+    let (mut x0, mut x1) = (0, 1);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 % r1);
+        (x0, x1) = (x1, sub_mod(x0, mul_mod(q % m, x1, m), m));
+    }
+
+    (r0 == 1).then_some(x0)
+}
+
+// base ^ exponent mod modulus, or None for a zero modulus or negative arguments
+pub fn mod_pow<T: PrimInt>(base: T, exponent: T, modulus: T) -> Option<T> {
+    let (mut base, mut exponent, modulus) = (base.to_u128()?, exponent.to_u128()?, modulus.to_u128()?);
+    if modulus == 0 {
+        return None;
+    }
+
+    let mut result = 1 % modulus;
+    base %= modulus;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+
+    T::from(result)
+}
+
+// x in 0..modulus with a * x = 1 (mod modulus), or None when a and the modulus are not coprime
+pub fn mod_inverse<T: PrimInt>(a: T, modulus: T) -> Option<T> {
+    let (a, modulus) = (a.to_u128()?, modulus.to_u128()?);
+    if modulus == 0 {
+        return None;
+    }
+
+    T::from(inverse_u128(a, modulus)?)
+}
+
+// Merges x = r1 (mod m1) and x = r2 (mod m2) into a single congruence x = r (mod lcm(m1, m2)). The moduli
+// do not need to be coprime, in which case there is no solution unless the residues agree modulo their
+// gcd. Also None when the combined modulus does not fit in T.
+pub fn combine_congruences<T: PrimInt>((r1, m1): (T, T), (r2, m2): (T, T)) -> Option<(T, T)> {
+    let (r1, m1, r2, m2) = (r1.to_u128()?, m1.to_u128()?, r2.to_u128()?, m2.to_u128()?);
+    if m1 == 0 || m2 == 0 {
+        return None;
+    }
+
+    let (r1, r2) = (r1 % m1, r2 % m2);
+    let g = gcd(m1, m2);
+
+    let difference = sub_mod(r2, r1 % m2, m2);
+    if !difference.is_multiple_of(g) {
+        return None;
+    }
+
+    // x = r1 + m1 * k where (m1 / g) * k = difference / g (mod m2 / g)
+    let reduced = m2 / g;
+    let k = mul_mod((difference / g) % reduced, inverse_u128((m1 / g) % reduced, reduced)?, reduced);
+    let modulus = m1.checked_mul(reduced)?;
+
+    Some((T::from(r1 + m1 * k)?, T::from(modulus)?))
+}
+
+// Solves a system of congruences x = r (mod m), returning the smallest non-negative solution and the
+// modulus of all solutions. An empty system is solved by every integer, i.e. x = 0 (mod 1).
+pub fn crt<T: PrimInt>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences.iter().try_fold((T::zero(), T::one()), |combined, &congruence| combine_congruences(combined, congruence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for a in -30i64..=30 {
+            for b in -30i64..=30 {
+                let (g, x, y) = extended_gcd(a, b);
+
+                assert_eq!(a * x + b * y, g, "a = {}, b = {}", a, b);
+                assert_eq!(g, gcd(a.abs(), b.abs()));
+            }
+        }
+    }
+
+    #[test]
+    fn lcm_reports_overflow() {
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(0u64, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn mod_pow_matches_repeated_multiplication() {
+        for modulus in 1u64..=30 {
+            for base in 0..40 {
+                for exponent in 0..20 {
+                    let expected = (0..exponent).fold(1 % modulus, |acc, _| acc * base % modulus);
+                    assert_eq!(mod_pow(base, exponent, modulus), Some(expected), "{}^{} mod {}", base, exponent, modulus);
+                }
+            }
+        }
+
+        assert_eq!(mod_pow(2u64, 10, 0), None);
+        assert_eq!(mod_pow(-2i64, 10, 7), None);
+    }
+
+    #[test]
+    fn mod_inverse_matches_search() {
+        for modulus in 1u64..=60 {
+            for a in 0..2 * modulus {
+                let expected = (0..modulus).find(|x| a * x % modulus == 1 % modulus);
+                assert_eq!(mod_inverse(a, modulus), expected, "{} mod {}", a, modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn combine_congruences_matches_search() {
+        for m1 in 1u64..=12 {
+            for m2 in 1u64..=12 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let modulus = lcm(m1, m2).unwrap();
+                        let expected = (0..modulus).find(|x| x % m1 == r1 && x % m2 == r2).map(|x| (x, modulus));
+
+                        assert_eq!(combine_congruences((r1, m1), (r2, m2)), expected, "x = {} mod {}, x = {} mod {}", r1, m1, r2, m2);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn crt_matches_search() {
+        assert_eq!(crt::<u32>(&[]), Some((0, 1)));
+
+        // Residues larger than their moduli are reduced first
+        for m1 in 1u32..=6 {
+            for m2 in 1u32..=6 {
+                for m3 in 1u32..=6 {
+                    for r in 0..12 {
+                        let congruences = [(r, m1), (r / 2, m2), (r / 3, m3)];
+                        let modulus = [m1, m2, m3].into_iter().fold(1, |acc, m| lcm(acc, m).unwrap());
+                        let expected = (0..modulus).find(|x| congruences.iter().all(|&(r, m)| x % m == r % m)).map(|x| (x, modulus));
+
+                        assert_eq!(crt(&congruences), expected, "{:?}", congruences);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn u128_moduli_do_not_overflow() {
+        // (-1) * (-2) = 2 modulo u128::MAX
+        assert_eq!(mul_mod(u128::MAX - 1, u128::MAX - 2, u128::MAX), 2);
+
+        // Fermat's little theorem for the Mersenne prime 2^127 - 1
+        let prime = (1u128 << 127) - 1;
+        assert_eq!(mod_pow(3, prime - 1, prime), Some(1));
+
+        let inverse = mod_inverse(u128::MAX - 1, u128::MAX).unwrap();
+        assert_eq!(mul_mod(u128::MAX - 1, inverse, u128::MAX), 1);
+
+        let (x, modulus) = combine_congruences((5u128, prime), (0, 2)).unwrap();
+        assert_eq!((x % prime, x % 2, modulus), (5, 0, 2 * prime));
+
+        // The combined modulus would not fit
+        assert_eq!(combine_congruences((5u128, prime), (7, u128::MAX)), None);
+    }
+}
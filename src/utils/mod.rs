@@ -1,2 +1,8 @@
 pub mod trie;
 pub mod print;
+pub mod graph;
+pub mod lines;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod input;
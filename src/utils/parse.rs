@@ -0,0 +1,259 @@
+// Small parser combinators for puzzle lines. A `Parser` is a cursor over the text: each method consumes
+// what it recognises and returns it, or fails with the column where the text stopped matching.
+
+use std::{fmt, str::FromStr};
+
+// How much of the remaining text to quote in an error
+const FOUND_CONTEXT: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    // Counted in characters from 1, so it lines up with the text even when it is not ASCII
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expected {} at column {} but found {}", self.expected, self.column, self.found)
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Parser<'a> {
+    input: &'a str,
+    // Byte offset of the first character not consumed yet
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            position: 0,
+        }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    // Error at the current position
+    pub fn error<T>(&self, expected: impl Into<String>) -> ParseResult<T> {
+        let found = match self.rest() {
+            "" => String::from("the end of the line"),
+            rest => format!("{:?}", rest.chars().take(FOUND_CONTEXT).collect::<String>()),
+        };
+
+        let column = self.input[..self.position].chars().count() + 1;
+
+        Err(ParseError { column, expected: expected.into(), found })
+    }
+
+    pub fn end(&self) -> ParseResult<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            self.error("the end of the line")
+        }
+    }
+
+    pub fn tag(&mut self, tag: &str) -> ParseResult<()> {
+        if self.try_tag(tag) {
+            Ok(())
+        } else {
+            self.error(format!("{:?}", tag))
+        }
+    }
+
+    // Consumes the tag if it comes next
+    pub fn try_tag(&mut self, tag: &str) -> bool {
+        let matched = self.rest().starts_with(tag);
+        if matched {
+            self.position += tag.len();
+        }
+
+        matched
+    }
+
+    // The first of the tags that comes next, so longer tags should be listed before their prefixes
+    pub fn one_of<'t>(&mut self, tags: &[&'t str]) -> ParseResult<&'t str> {
+        match tags.iter().find(|&&tag| self.try_tag(tag)) {
+            Some(&tag) => Ok(tag),
+            None => self.error(format!("one of {:?}", tags)),
+        }
+    }
+
+    // At least one character matching the predicate
+    pub fn take_while(&mut self, expected: &str, predicate: impl Fn(char) -> bool) -> ParseResult<&'a str> {
+        let rest = self.rest();
+        let length = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+
+        if length == 0 {
+            return self.error(expected);
+        }
+
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    // At least one whitespace character
+    pub fn whitespace(&mut self) -> ParseResult<()> {
+        self.take_while("whitespace", char::is_whitespace).map(|_| ())
+    }
+
+    fn number<T: FromStr>(&mut self, expected: &str, start: usize) -> ParseResult<T> {
+        if self.take_while(expected, |c| c.is_ascii_digit()).is_err() {
+            self.position = start;
+            return self.error(expected);
+        }
+
+        match self.input[start..self.position].parse::<T>() {
+            Ok(number) => Ok(number),
+            Err(_) => {
+                self.position = start;
+                self.error(format!("{} that fits in {}", expected, std::any::type_name::<T>()))
+            }
+        }
+    }
+
+    pub fn unsigned<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = self.position;
+        self.number("an unsigned integer", start)
+    }
+
+    // Integer with an optional leading - or +
+    pub fn signed<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = self.position;
+        if !self.try_tag("-") {
+            self.try_tag("+");
+        }
+
+        self.number("an integer", start)
+    }
+
+    // One or more items with the separator between them. Once a separator is seen another item must follow.
+    pub fn separated<T>(&mut self, separator: &str, mut item: impl FnMut(&mut Self) -> ParseResult<T>) -> ParseResult<Vec<T>> {
+        let mut items = vec![item(self)?];
+
+        while self.try_tag(separator) {
+            items.push(item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    // One or more items separated by runs of whitespace. Whitespace that is not followed by another item
+    // is left for the caller, so the list can be followed by more fields.
+    pub fn whitespace_separated<T>(&mut self, mut item: impl FnMut(&mut Self) -> ParseResult<T>) -> ParseResult<Vec<T>> {
+        let mut items = vec![item(self)?];
+
+        loop {
+            let mut lookahead = *self;
+            match lookahead.whitespace().and_then(|_| item(&mut lookahead)) {
+                Ok(next) => {
+                    items.push(next);
+                    *self = lookahead;
+                }
+                Err(_) => return Ok(items),
+            }
+        }
+    }
+
+    // key=value pairs with the separator between them, in the order they appear
+    pub fn key_values<K, V>(
+        &mut self,
+        separator: &str,
+        assignment: &str,
+        mut key: impl FnMut(&mut Self) -> ParseResult<K>,
+        mut value: impl FnMut(&mut Self) -> ParseResult<V>,
+    ) -> ParseResult<Vec<(K, V)>> {
+        self.separated(separator, |parser| {
+            let key = key(parser)?;
+            parser.tag(assignment)?;
+
+            Ok((key, value(parser)?))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(column: usize, expected: &str, found: &str) -> ParseError {
+        ParseError { column, expected: expected.to_owned(), found: found.to_owned() }
+    }
+
+    #[test]
+    fn tag_reports_where_it_failed() {
+        let mut parser = Parser::new("Card 1: 41");
+
+        assert_eq!(parser.tag("Card "), Ok(()));
+        assert_eq!(parser.tag("Game"), Err(error_at(6, "\"Game\"", "\"1: 41\"")));
+        assert_eq!(parser.rest(), "1: 41");
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let mut parser = Parser::new("é→x");
+
+        assert_eq!(parser.tag("é→"), Ok(()));
+        assert_eq!(parser.tag("y"), Err(error_at(3, "\"y\"", "\"x\"")));
+        assert_eq!(parser.end().unwrap_err().to_string(), "Expected the end of the line at column 3 but found \"x\"");
+    }
+
+    #[test]
+    fn unsigned_rejects_signs_and_overflow() {
+        assert_eq!(Parser::new("42x").unsigned::<u8>(), Ok(42));
+        assert_eq!(Parser::new("-1").unsigned::<u8>(), Err(error_at(1, "an unsigned integer", "\"-1\"")));
+        assert_eq!(Parser::new("").unsigned::<u8>(), Err(error_at(1, "an unsigned integer", "the end of the line")));
+
+        // The cursor is left at the start of the number, so the error points there
+        let mut parser = Parser::new("a=256");
+        parser.tag("a=").unwrap();
+        assert_eq!(parser.unsigned::<u8>(), Err(error_at(3, "an unsigned integer that fits in u8", "\"256\"")));
+        assert_eq!(parser.rest(), "256");
+    }
+
+    #[test]
+    fn signed_accepts_either_sign() {
+        assert_eq!(Parser::new("-12").signed::<i32>(), Ok(-12));
+        assert_eq!(Parser::new("+12").signed::<i32>(), Ok(12));
+        assert_eq!(Parser::new("- 12").signed::<i32>(), Err(error_at(1, "an integer", "\"- 12\"")));
+    }
+
+    #[test]
+    fn whitespace_separated_leaves_trailing_text() {
+        let mut parser = Parser::new("41 48  83 | 86");
+
+        assert_eq!(parser.whitespace_separated(Parser::unsigned::<u32>), Ok(vec![41, 48, 83]));
+        assert_eq!(parser.rest(), " | 86");
+
+        let mut parser = Parser::new("| 86");
+        assert_eq!(parser.whitespace_separated(Parser::unsigned::<u32>), Err(error_at(1, "an unsigned integer", "\"| 86\"")));
+    }
+
+    #[test]
+    fn separated_requires_an_item_after_each_separator() {
+        let mut parser = Parser::new("1,2,");
+
+        assert_eq!(parser.separated(",", Parser::unsigned::<u32>), Err(error_at(5, "an unsigned integer", "the end of the line")));
+    }
+
+    #[test]
+    fn key_values_keeps_order() {
+        let mut parser = Parser::new("x=787,m=2655");
+        let name = |parser: &mut Parser<'_>| parser.take_while("a name", |c| c.is_ascii_alphabetic()).map(str::to_owned);
+
+        assert_eq!(parser.key_values(",", "=", name, Parser::unsigned::<u32>), Ok(vec![(String::from("x"), 787), (String::from("m"), 2655)]));
+        assert_eq!(parser.end(), Ok(()));
+    }
+}
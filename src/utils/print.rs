@@ -7,3 +7,11 @@ fn print_grid<T: std::fmt::Display>(grid: &Vec<Vec<T>>) {
     }
     println!();
 }
+
+// Prints a part's answer, or the error that kept it from being found
+pub fn print_answer<T: std::fmt::Display, E: std::fmt::Display>(part: &str, answer: Result<T, E>) {
+    match answer {
+        Ok(answer) => println!("[{} :: INFO] Answer: {}", part, answer),
+        Err(e) => eprintln!("[{} :: ERROR] {}", part, e),
+    }
+}